@@ -2,12 +2,12 @@ use std::{env, fs, path::Path};
 use types::{
     bitboard::BitBoard,
     slider::Slider,
-    sliders::{common::*, magic::*},
+    sliders::{black_magic::*, common::*, magic::*},
     square::Square,
 };
 
-fn bishop_table() -> [BitBoard; BISHOP_SIZE] {
-    let mut table = [BitBoard::EMPTY; BISHOP_SIZE];
+fn bishop_table() -> Vec<BitBoard> {
+    let mut table = vec![BitBoard::EMPTY; BISHOP_SIZE];
     for sq in 0..Square::NUM {
         let blockers = BitBoard(BISHOP_BLOCKERS[sq]);
         let magic = BISHOP_MAGICS[sq];
@@ -19,8 +19,8 @@ fn bishop_table() -> [BitBoard; BISHOP_SIZE] {
     table
 }
 
-fn rook_table() -> [BitBoard; ROOK_SIZE] {
-    let mut table = [BitBoard::EMPTY; ROOK_SIZE];
+fn rook_table() -> Vec<BitBoard> {
+    let mut table = vec![BitBoard::EMPTY; ROOK_SIZE];
     for sq in 0..Square::NUM {
         let blockers = BitBoard(ROOK_BLOCKERS[sq]);
         let magic = ROOK_MAGICS[sq];
@@ -32,21 +32,62 @@ fn rook_table() -> [BitBoard; ROOK_SIZE] {
     table
 }
 
-fn main() {
-    let out_dir = env::var_os("OUT_DIR").unwrap();
-    let dest_path = Path::new(&out_dir).join("slider_moves.rs");
+/// Tables for `Pext`, which shares the offsets of fancy magics since both index with
+/// exactly as many bits as there are relevant blockers
+fn pext_table(slider: Slider, size: usize, offsets: &[usize; Square::NUM]) -> Vec<BitBoard> {
+    let mut table = vec![BitBoard::EMPTY; size];
+    for sq in 0..Square::NUM {
+        let blockers = slider.blockers(Square(sq));
+        for subset in subsets(blockers) {
+            table[offsets[sq] + pext_software(subset.0, blockers.0) as usize] = slider.pseudo_moves(Square(sq), subset);
+        }
+    }
+    table
+}
 
-    fs::write(
-        dest_path,
-        format!(
-            "pub const fn get_bishop_table() -> [u64; {BISHOP_SIZE}] {{ {:?} }}
+fn black_magic_table() -> Vec<BitBoard> {
+    let mut table = vec![BitBoard::EMPTY; BLACK_MAGIC_SIZE];
+    for sq in 0..Square::NUM {
+        let sliders = [
+            (Slider::Bishop, BISHOP_BLACK_MAGICS[sq], BISHOP_BLACK_OFFSETS[sq]),
+            (Slider::Rook, ROOK_BLACK_MAGICS[sq], ROOK_BLACK_OFFSETS[sq]),
+        ];
+        for (slider, magic, offset) in sliders {
+            let blockers = slider.blockers(Square(sq));
+            for subset in subsets(blockers) {
+                let idx = black_magic_index(subset, !blockers.0, magic, 64 - slider.shift_count()).wrapping_add(offset);
+                table[idx] = slider.pseudo_moves(Square(sq), subset);
+            }
+        }
+    }
+    table
+}
 
-            pub const fn get_rook_table() -> [u64; {ROOK_SIZE}] {{ {:?} }}
-        ",
-            bishop_table(), rook_table()
-        ),
-    )
-    .unwrap();
+/// Writes the attacks as raw u64 values in the byte order of the target, for `include_table!`
+fn write_table(name: &str, table: &[BitBoard]) {
+    let big_endian = env::var("CARGO_CFG_TARGET_ENDIAN").unwrap() == "big";
+    let bytes: Vec<u8> = table
+        .iter()
+        .flat_map(|attacks| match big_endian {
+            true => attacks.0.to_be_bytes(),
+            false => attacks.0.to_le_bytes(),
+        })
+        .collect();
+    let out_dir = env::var_os("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join(name), bytes).unwrap();
+}
+
+fn main() {
+    write_table("bishop_table.bin", &bishop_table());
+    write_table("rook_table.bin", &rook_table());
+    // The tables of the optional backends are only needed, and only compiled, with their features
+    if env::var_os("CARGO_FEATURE_SLIDERS_BLACK_MAGIC").is_some() {
+        write_table("black_magic_table.bin", &black_magic_table());
+    }
+    if env::var_os("CARGO_FEATURE_SLIDERS_PEXT").is_some() {
+        write_table("bishop_pext_table.bin", &pext_table(Slider::Bishop, BISHOP_SIZE, &BISHOP_OFFSETS));
+        write_table("rook_pext_table.bin", &pext_table(Slider::Rook, ROOK_SIZE, &ROOK_OFFSETS));
+    }
 
     println!("cargo:rerun-if-changed=build.rs");
 }
@@ -0,0 +1,101 @@
+//! Compares the incrementally maintained attack maps of `AttackBoard` with computing
+//! `Board::danger` from scratch, run with `--release --features attack-maps`.
+
+use std::hint::black_box;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use engine::attacks::AttackBoard;
+use engine::board::{Board, STARTPOS};
+use engine::random::Rng;
+
+const POSITIONS: usize = 10_000;
+const ROUNDS: usize = 100;
+
+fn per(elapsed: Duration, count: usize) -> f64 {
+    elapsed.as_nanos() as f64 / count as f64
+}
+
+/// Walks the tree, querying the danger mask and checkers at every node
+fn walk_board(board: &Board, depth: usize) -> u64 {
+    black_box((board.danger(), board.checkers()));
+    if depth == 0 {
+        return 1;
+    }
+    board.moves().iter().map(|mv| {
+        let mut next = *board;
+        next.apply_move(mv);
+        walk_board(&next, depth - 1)
+    }).sum()
+}
+
+fn walk_maps(maps: &AttackBoard, depth: usize) -> u64 {
+    black_box((maps.danger(), maps.checkers()));
+    if depth == 0 {
+        return 1;
+    }
+    maps.board().moves().iter().map(|mv| {
+        let mut next = *maps;
+        next.apply_move(mv);
+        walk_maps(&next, depth - 1)
+    }).sum()
+}
+
+fn main() {
+    let start = Board::from_str(STARTPOS).unwrap();
+    let mut rng = Rng::new(0);
+    let boards: Vec<Board> = (0..POSITIONS).map(|i| start.random_playout(i % 120, &mut rng).0).collect();
+    let maps: Vec<AttackBoard> = boards.iter().map(|board| AttackBoard::new(*board)).collect();
+    for map in &maps {
+        assert_eq!(map.danger(), map.board().danger(), "{}", map.board().fen());
+    }
+
+    println!("danger() per position");
+    let timer = Instant::now();
+    for _ in 0..ROUNDS {
+        for board in &boards {
+            black_box(black_box(board).danger());
+        }
+    }
+    println!("{:>14}: {:.1} ns", "from scratch", per(timer.elapsed(), ROUNDS * POSITIONS));
+    let timer = Instant::now();
+    for _ in 0..ROUNDS {
+        for map in &maps {
+            black_box(black_box(map).danger());
+        }
+    }
+    println!("{:>14}: {:.1} ns", "attack maps", per(timer.elapsed(), ROUNDS * POSITIONS));
+
+    println!("\napply_move + undo_move per move");
+    let moves: Vec<_> = boards.iter().map(|board| board.moves()).collect();
+    let count: usize = moves.iter().map(|moves| moves.len()).sum();
+    let timer = Instant::now();
+    for (board, moves) in boards.iter().zip(&moves) {
+        let mut board = *board;
+        for mv in moves.iter() {
+            board.apply_move(mv);
+            board.undo_move(mv);
+        }
+        black_box(board);
+    }
+    println!("{:>14}: {:.1} ns", "board", per(timer.elapsed(), count));
+    let timer = Instant::now();
+    for (map, moves) in maps.iter().zip(&moves) {
+        let mut map = *map;
+        for mv in moves.iter() {
+            map.apply_move(mv);
+            map.undo_move(mv);
+        }
+        black_box(map);
+    }
+    println!("{:>14}: {:.1} ns", "attack maps", per(timer.elapsed(), count));
+
+    let kiwipete = Board::from_str("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+    println!("\nKiwipete depth 4, danger() and checkers at every node");
+    let timer = Instant::now();
+    let nodes = walk_board(&kiwipete, 4);
+    println!("{:>14}: {:.1} ns per node", "from scratch", per(timer.elapsed(), nodes as usize));
+    let timer = Instant::now();
+    let nodes = walk_maps(&AttackBoard::new(kiwipete), 4);
+    println!("{:>14}: {:.1} ns per node", "attack maps", per(timer.elapsed(), nodes as usize));
+}
@@ -0,0 +1,60 @@
+//! Compares legal king destinations from the full `danger()` map with targeted
+//! per-square `attacked` tests, then reports perft NPS for the one move generation
+//! uses. Run it with and without `--features king-attackers` to time both in perft.
+
+use std::hint::black_box;
+use std::str::FromStr;
+use std::time::Instant;
+
+use engine::board::Board;
+use engine::perft::perft_timed;
+use engine::random::Rng;
+use types::bitboard::BitBoard;
+use types::nonsliders::common::KING_MOVES;
+
+const POSITIONS: usize = 10_000;
+const ROUNDS: usize = 100;
+
+fn by_danger(board: &Board) -> BitBoard {
+    BitBoard(KING_MOVES[board.king().0]) & !board.us() & !board.danger()
+}
+
+fn by_attackers(board: &Board) -> BitBoard {
+    let king = board.king();
+    let without_king = board.occupied() ^ king.bitboard();
+    let mut moves = BitBoard::EMPTY;
+    for to in BitBoard(KING_MOVES[king.0]) & !board.us() {
+        if !board.attacked(to, without_king) {
+            moves |= to.bitboard();
+        }
+    }
+    moves
+}
+
+fn main() {
+    let start = Board::from_str("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    let mut rng = Rng::new(0);
+    let positions: Vec<Board> = (0..POSITIONS).map(|i| start.random_playout(i % 120, &mut rng).0).collect();
+    for board in &positions {
+        assert_eq!(by_danger(board), by_attackers(board), "{}", board.fen());
+    }
+
+    for (name, king_moves) in [("danger()", by_danger as fn(&Board) -> BitBoard), ("attacked", by_attackers)] {
+        let timer = Instant::now();
+        for _ in 0..ROUNDS {
+            for board in &positions {
+                black_box(king_moves(black_box(board)));
+            }
+        }
+        let elapsed = timer.elapsed();
+        println!("{name:>12}: {:.1} ns per position", elapsed.as_nanos() as f64 / (ROUNDS * POSITIONS) as f64);
+    }
+
+    println!("\nMove generation uses {}", if cfg!(feature = "king-attackers") { "attacked" } else { "danger()" });
+    let kiwipete = Board::from_str("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+    println!("\nKiwipete perft 5, bulk counting\n{}", perft_timed(&kiwipete, 5, true));
+    println!("\nKiwipete perft 5, every leaf made\n{}", perft_timed(&kiwipete, 5, false));
+    // Few enemy sliders and a roaming king, where one full map costs less than eight tests
+    let endgame = Board::from_str("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1").unwrap();
+    println!("\nPosition 3 perft 7, bulk counting\n{}", perft_timed(&endgame, 7, true));
+}
@@ -1,30 +1,73 @@
 use std::str::FromStr;
 use std::env;
 use engine::board::Board;
+use std::time::Instant;
+use engine::perft::{divide, perft_hashed_parallel, perft_parallel, perft_stats, perft_timed, PerftReport, PerftTable};
+
+const USAGE: &str = "Usage:
+  perft <fen> <depth> [--divide]   six FEN fields, prints `e2e4: 20` per root move with --divide
+  perft <fen> <depth> --stats      counts captures, castles, checks, mates... at the leaves
+  perft <fen> <depth> --no-bulk    makes every leaf move instead of counting them in bulk
+  perft <fen> <depth> --threads=N  splits the tree over N threads
+  perft <fen> <depth> --hash=MB    caches subtree counts in a table of MB megabytes
+  perft <depth> <fen> [moves]      perftree convention, the moves are UCI and space separated";
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    assert!(args.len() >= 8, "Please provide valid FEN and depth");
-    let fen = &args[1..7].join(" ");
-    let depth = args[7].parse::<usize>().expect("Invalid depth");
-    let brd = Board::from_str(fen).expect("Invalid FEN");
+    let args: Vec<String> = env::args().skip(1).collect();
 
-    fn perft(brd: &Board, depth: usize) -> usize {
-        let mut brd = *brd;
-        let mut nodes = 0;
-        if depth == 0 {
-            return 1;
+    // perftree calls `perft "$depth" "$fen" "$moves"` and expects `e2e4 20` lines,
+    // an empty line and the total
+    if let Some(depth) = args.first().and_then(|arg| arg.parse::<usize>().ok()) {
+        assert!((2..=3).contains(&args.len()), "{USAGE}");
+        let mut brd = Board::from_str(&args[1]).expect("Invalid FEN");
+        for uci in args.get(2).map(|moves| moves.split_whitespace()).into_iter().flatten() {
+            let mv = brd.parse_uci(uci).expect("Invalid move");
+            brd.apply_move(&mv);
         }
-        for piece_moves in brd.moves() {
-            for mv in piece_moves.convert(brd.turn) {
-                brd.apply_move(&mv);
-                nodes += perft(&mut brd, depth - 1); 
-                brd.undo_move(&mv);            
-            }
+        let counts = divide(&brd, depth);
+        for (mv, nodes) in &counts {
+            println!("{mv} {nodes}");
         }
-        nodes
+        println!("\n{}", counts.iter().map(|(_, nodes)| nodes).sum::<u64>());
+        return;
+    }
+
+    let divide_mode = args.iter().any(|arg| arg == "--divide");
+    let bulk = !args.iter().any(|arg| arg == "--no-bulk");
+    let stats_mode = args.iter().any(|arg| arg == "--stats");
+    let threads = args.iter()
+        .find_map(|arg| arg.strip_prefix("--threads="))
+        .map_or(1, |threads| threads.parse::<usize>().expect("Invalid thread count"));
+    let hash = args.iter()
+        .find_map(|arg| arg.strip_prefix("--hash="))
+        .map(|megabytes| megabytes.parse::<usize>().expect("Invalid hash size"));
+    let args: Vec<&String> = args.iter().filter(|arg| !arg.starts_with("--")).collect();
+    assert!(args.len() == 7, "{USAGE}");
+    let fen = args[0..6].iter().map(|arg| arg.as_str()).collect::<Vec<_>>().join(" ");
+    let depth = args[6].parse::<usize>().expect("Invalid depth");
+    let brd = Board::from_str(&fen).expect("Invalid FEN");
+
+    if stats_mode {
+        println!("{}", perft_stats(&brd, depth));
+        return;
     }
 
-    let nodes = perft(&brd, depth);
-    println!("\nNodes searched: {nodes}");
+    if divide_mode {
+        let counts = divide(&brd, depth);
+        for (mv, nodes) in &counts {
+            println!("{mv}: {nodes}");
+        }
+        println!("\nNodes searched: {}", counts.iter().map(|(_, nodes)| nodes).sum::<u64>());
+        return;
+    }
+    if threads > 1 || hash.is_some() {
+        let start = Instant::now();
+        let nodes = match hash {
+            Some(megabytes) => perft_hashed_parallel(&brd, depth, threads, &PerftTable::new(megabytes)),
+            None => perft_parallel(&brd, depth, threads)
+        };
+        println!("\n{}", PerftReport { nodes, elapsed: start.elapsed() });
+        return;
+    }
+    println!("\n{}", perft_timed(&brd, depth, bulk));
 }
@@ -0,0 +1,56 @@
+//! Times every slider attack implementation on random squares and occupancies,
+//! run with `--release`. The backend used by move generation is picked by feature.
+
+use std::any::type_name;
+use std::hint::black_box;
+use std::time::Instant;
+
+use engine::random::Rng;
+#[cfg(feature = "sliders-black-magic")]
+use engine::sliders::BlackMagic;
+#[cfg(feature = "sliders-pext")]
+use engine::sliders::Pext;
+use engine::sliders::{Backend, Dumb7Fill, Hyperbola, Kindergarten, Magic, SliderAttacks};
+use types::bitboard::BitBoard;
+use types::square::Square;
+
+const SAMPLES: usize = 4096;
+const ROUNDS: usize = 2_000;
+
+fn bench<S: SliderAttacks>(samples: &[(Square, BitBoard)]) {
+    // Warms up the tables, and lets Pext pick its lookups
+    let mut checksum = 0;
+    for &(sq, occupied) in samples {
+        checksum ^= S::queen(sq, occupied).0;
+    }
+    let timer = Instant::now();
+    for _ in 0..ROUNDS {
+        for &(sq, occupied) in samples {
+            checksum ^= S::queen(black_box(sq), black_box(occupied)).0;
+        }
+    }
+    let elapsed = timer.elapsed();
+    black_box(checksum);
+    println!("{:>14}: {:.2} ns per queen lookup", S::NAME, elapsed.as_nanos() as f64 / (ROUNDS * SAMPLES) as f64);
+}
+
+fn main() {
+    let mut rng = Rng::new(0);
+    // Roughly a middlegame's worth of pieces
+    let samples: Vec<(Square, BitBoard)> = (0..SAMPLES)
+        .map(|_| (Square(rng.below(64)), BitBoard(rng.next_u64() & rng.next_u64())))
+        .collect();
+
+    println!("Move generation uses {}", type_name::<Backend>());
+    bench::<Magic>(&samples);
+    #[cfg(feature = "sliders-pext")]
+    {
+        println!("BMI2 {}", if Pext::available() { "available" } else { "unavailable" });
+        bench::<Pext>(&samples);
+    }
+    #[cfg(feature = "sliders-black-magic")]
+    bench::<BlackMagic>(&samples);
+    bench::<Hyperbola>(&samples);
+    bench::<Kindergarten>(&samples);
+    bench::<Dumb7Fill>(&samples);
+}
@@ -0,0 +1,301 @@
+use types::bitboard::BitBoard;
+use types::color::Color;
+use types::moves::{Move, MoveKind};
+use types::nonsliders::common::{KING_MOVES, KNIGHT_MOVES, PAWN_ATTACKS};
+use types::piece::Piece;
+use types::square::Square;
+
+use crate::board::{bishop_attacks, rook_attacks, Board, PIECES};
+
+/// Piece values used by static exchange evaluation, indexed like `Piece`
+pub const SEE_VALUES: [i32; 6] = [20000, 900, 500, 300, 300, 100];
+
+/// Board that keeps per-square and per-colour attack maps up to date as moves are
+/// applied and undone, so attack queries are lookups instead of slider computations
+#[derive(Clone, Copy, Debug)]
+pub struct AttackBoard {
+    board: Board,
+    from: [BitBoard; 64], // squares attacked by the piece on each square
+    to: [BitBoard; 64], // pieces of both colours attacking each square
+    counts: [[u8; 64]; 2], // number of white, black pieces attacking each square
+    attacked: [BitBoard; 2] // squares attacked by white, black
+}
+
+impl AttackBoard {
+    /// Computes the attack maps of `board` from scratch
+    pub fn new(board: Board) -> Self {
+        let mut maps = AttackBoard {
+            board,
+            from: [BitBoard::EMPTY; 64],
+            to: [BitBoard::EMPTY; 64],
+            counts: [[0; 64]; 2],
+            attacked: [BitBoard::EMPTY; 2]
+        };
+        for sq in board.occupied() {
+            maps.add(sq);
+        }
+        maps
+    }
+
+    pub const fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Returns the squares attacked by the piece on `sq`
+    pub const fn attacks_from(&self, sq: Square) -> BitBoard {
+        self.from[sq.0]
+    }
+
+    /// Returns the pieces of both colours attacking `sq`
+    pub const fn attackers_to(&self, sq: Square) -> BitBoard {
+        self.to[sq.0]
+    }
+
+    /// Returns the squares attacked by at least one piece of `color`
+    pub const fn attacked_by(&self, color: Color) -> BitBoard {
+        self.attacked[color as usize]
+    }
+
+    /// Returns the enemy pieces giving check to the side to move
+    pub fn checkers(&self) -> BitBoard {
+        self.to[self.board.king().0] & self.board.them()
+    }
+
+    /// Returns the same mask as `Board::danger`
+    ///
+    /// The maps see our king as a blocker, so only the rays of checking sliders
+    /// are recomputed to extend them behind the king.
+    pub fn danger(&self) -> BitBoard {
+        let king = self.board.king();
+        let without_king = self.board.occupied() ^ king.bitboard();
+        let mut mask = self.attacked[1 - self.board.turn as usize];
+        for checker in self.checkers() {
+            let piece = self.board.squares[checker.0].expect("no piece giving check");
+            mask |= slider_attacks(piece, checker, without_king);
+        }
+        mask
+    }
+
+    /// Counts the squares attacked by the knights, bishops, rooks and queens of
+    /// `color` that are not occupied by its own pieces
+    pub fn mobility(&self, color: Color) -> u32 {
+        let own = self.board.all[color as usize];
+        let pieces = self.board.pieces[color as usize];
+        (pieces[1] | pieces[2] | pieces[3] | pieces[4])
+            .into_iter()
+            .map(|sq| (self.from[sq.0] & !own).len())
+            .sum()
+    }
+
+    /// Static exchange evaluation of `mv`, the material the side to move wins
+    /// when both sides keep recapturing on the target square with their least
+    /// valuable attacker for as long as it pays off. Pins are ignored.
+    pub fn see(&self, mv: &Move) -> i32 {
+        let board = &self.board;
+        let target = mv.to();
+        let [white, black] = board.pieces;
+        let diagonal = white[1] | white[3] | black[1] | black[3];
+        let orthogonal = white[1] | white[2] | black[1] | black[2];
+        let mut occupied = board.occupied();
+
+        let mut gain = [0; 32];
+        gain[0] = match mv.kind() {
+            MoveKind::EnPassant => {
+                occupied ^= target.bitboard().shift_color(8, !board.turn);
+                SEE_VALUES[5]
+            }
+            _ => board.squares[target.0].map_or(0, |piece| SEE_VALUES[piece as usize])
+        };
+        let mut piece = match mv.kind().promotion_piece() {
+            Some(promotion) => {
+                gain[0] += SEE_VALUES[Piece::from(promotion) as usize] - SEE_VALUES[5];
+                Piece::from(promotion)
+            }
+            None => board.squares[mv.from().0].expect("no piece to move")
+        };
+        let mut from = mv.from().bitboard();
+        let mut attackers = self.to[target.0];
+        let mut side = board.turn;
+        let mut depth = 0;
+        loop {
+            depth += 1;
+            // Speculative score, should the piece now on the target square be captured
+            gain[depth] = SEE_VALUES[piece as usize] - gain[depth - 1];
+            if (-gain[depth - 1]).max(gain[depth]) < 0 {
+                break;
+            }
+            occupied ^= from;
+            attackers &= occupied;
+            // Removing a piece on a line through the target may reveal a slider behind it
+            if piece != Piece::Knight && piece != Piece::King {
+                attackers |= ((bishop_attacks(target, occupied) & diagonal) | (rook_attacks(target, occupied) & orthogonal)) & occupied;
+            }
+            side = !side;
+            let ours = attackers & board.all[side as usize];
+            let Some(next) = (0..6).rev().find(|&i| !(ours & board.pieces[side as usize][i]).is_empty()) else {
+                break;
+            };
+            piece = PIECES[next];
+            from = BitBoard(1 << (ours & board.pieces[side as usize][next]).0.trailing_zeros());
+            if depth == gain.len() - 1 {
+                break;
+            }
+        }
+        while depth > 1 {
+            depth -= 1;
+            gain[depth - 1] = -(-gain[depth - 1]).max(gain[depth]);
+        }
+        gain[0]
+    }
+
+    /// Applies the move, recomputing only the pieces on changed squares and the
+    /// sliders whose rays pass through them
+    pub fn apply_move(&mut self, mv: &Move) {
+        self.update(|board| board.apply_move(mv));
+    }
+
+    /// Undoes the move that was just applied, see `Board::undo_move`
+    pub fn undo_move(&mut self, mv: &Move) {
+        self.update(|board| board.undo_move(mv));
+    }
+
+    fn update<F: FnOnce(&mut Board)>(&mut self, change: F) {
+        let mut next = self.board;
+        change(&mut next);
+
+        let mut changed = BitBoard::EMPTY;
+        for color in 0..2 {
+            for piece in 0..6 {
+                changed |= self.board.pieces[color][piece] ^ next.pieces[color][piece];
+            }
+        }
+        // A slider's attacks only change if it attacked one of the changed squares before,
+        // either the square was empty on its ray or it blocked the ray
+        let [white, black] = self.board.pieces;
+        let sliders = white[1] | white[2] | white[3] | black[1] | black[2] | black[3];
+        let mut affected = changed & self.board.occupied();
+        for sq in changed {
+            affected |= self.to[sq.0] & sliders;
+        }
+
+        for sq in affected {
+            self.remove(sq);
+        }
+        self.board = next;
+        for sq in (affected | changed) & self.board.occupied() {
+            self.add(sq);
+        }
+    }
+
+    fn color_on(&self, sq: Square) -> usize {
+        (self.board.all[0] & sq.bitboard()).is_empty() as usize
+    }
+
+    fn add(&mut self, sq: Square) {
+        let color = self.color_on(sq);
+        let piece = self.board.squares[sq.0].expect("no piece to add");
+        let attacks = match piece {
+            Piece::King => BitBoard(KING_MOVES[sq.0]),
+            Piece::Knight => BitBoard(KNIGHT_MOVES[sq.0]),
+            Piece::Pawn => BitBoard(PAWN_ATTACKS[color][sq.0]),
+            slider => slider_attacks(slider, sq, self.board.occupied())
+        };
+        self.from[sq.0] = attacks;
+        self.attacked[color] |= attacks;
+        for target in attacks {
+            self.to[target.0] |= sq.bitboard();
+            self.counts[color][target.0] += 1;
+        }
+    }
+
+    fn remove(&mut self, sq: Square) {
+        let color = self.color_on(sq);
+        for target in self.from[sq.0] {
+            self.to[target.0] ^= sq.bitboard();
+            self.counts[color][target.0] -= 1;
+            if self.counts[color][target.0] == 0 {
+                self.attacked[color] ^= target.bitboard();
+            }
+        }
+        self.from[sq.0] = BitBoard::EMPTY;
+    }
+}
+
+/// Attacks of a queen, rook or bishop on `sq`, empty for other pieces
+fn slider_attacks(piece: Piece, sq: Square, occupied: BitBoard) -> BitBoard {
+    match piece {
+        Piece::Queen => rook_attacks(sq, occupied) | bishop_attacks(sq, occupied),
+        Piece::Rook => rook_attacks(sq, occupied),
+        Piece::Bishop => bishop_attacks(sq, occupied),
+        _ => BitBoard::EMPTY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use types::color::Color;
+
+    use crate::attacks::AttackBoard;
+    use crate::board::{Board, STARTPOS};
+    use crate::random::Rng;
+
+    fn assert_maps_eq(maps: &AttackBoard) {
+        let fresh = AttackBoard::new(*maps.board());
+        let fen = maps.board().fen();
+        assert_eq!(maps.from, fresh.from, "{fen}");
+        assert_eq!(maps.to, fresh.to, "{fen}");
+        assert_eq!(maps.counts, fresh.counts, "{fen}");
+        assert_eq!(maps.attacked, fresh.attacked, "{fen}");
+        assert_eq!(maps.danger(), maps.board().danger(), "{fen}");
+        assert_eq!(maps.checkers(), maps.board().checkers(), "{fen}");
+    }
+
+    #[test]
+    fn test_incremental_maps() {
+        let mut rng = Rng::new(0);
+        for _ in 0..50 {
+            let mut maps = AttackBoard::new(Board::from_str(STARTPOS).unwrap());
+            for _ in 0..150 {
+                let moves = maps.board().moves();
+                // Every move is undone once to check both directions
+                for mv in moves.iter() {
+                    let before = maps;
+                    maps.apply_move(mv);
+                    assert_maps_eq(&maps);
+                    maps.undo_move(mv);
+                    assert_eq!(maps.from, before.from);
+                    assert_eq!(maps.attacked, before.attacked);
+                }
+                let Some(mv) = rng.choose(&moves) else { break };
+                maps.apply_move(mv);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mobility() {
+        let maps = AttackBoard::new(Board::from_str(STARTPOS).unwrap());
+        assert_eq!(maps.mobility(Color::White), 4);
+        let maps = AttackBoard::new(Board::from_str("4k3/8/8/8/3Q4/8/8/4K3 w - - 0 1").unwrap());
+        assert_eq!(maps.mobility(Color::White), 27);
+        assert_eq!(maps.mobility(Color::Black), 0);
+    }
+
+    #[test]
+    fn test_see() {
+        let see = |fen: &str, uci: &str| {
+            let board = Board::from_str(fen).unwrap();
+            AttackBoard::new(board).see(&board.parse_uci(uci).unwrap())
+        };
+        // Undefended pawn
+        assert_eq!(see("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1", "e1e5"), 100);
+        // Knight takes a pawn defended through a battery of x-rays
+        assert_eq!(see("1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1", "d3e5"), -200);
+        // Rook takes a rook, the recapture loses to the queen behind
+        assert_eq!(see("3r2k1/5p2/8/3r4/8/8/3R4/3Q2K1 w - - 0 1", "d2d5"), 500);
+        assert_eq!(see("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"), 100);
+        assert_eq!(see(STARTPOS, "e2e4"), 0);
+    }
+}
@@ -4,18 +4,16 @@ use thiserror::Error;
 use types::bitboard::BitBoard;
 use types::color::Color;
 use types::nonsliders::common::{KNIGHT_MOVES, KING_MOVES, PAWN_ATTACKS};
-use types::piece::{Piece, PromotionPiece};
-use types::moves::{PieceMoves, Move, MoveFlags::Promotion};
-use types::sliders::magic::magic_index;
+use types::piece::Piece;
+use types::moves::{PieceMoves, Move, MoveKind, MoveList};
 use types::square::Square;
-use types::sliders::common::{ROOK_BLOCKERS, BISHOP_BLOCKERS, ROOK_MAGICS, BISHOP_MAGICS, BISHOP_ATTACKS, ROOK_ATTACKS, ROOK_OFFSETS, BISHOP_OFFSETS, BISHOP_SIZE, ROOK_SIZE, BISHOP_SHIFTS, ROOK_SHIFTS};
+use types::sliders::common::{BISHOP_ATTACKS, ROOK_ATTACKS};
 
 use crate::helpers::{square_idx, piece_idx, squares_between};
+use crate::sliders::{Backend, SliderAttacks};
+use crate::zobrist::ZOBRIST;
 
-include!(concat!(env!("OUT_DIR"), "/slider_moves.rs"));
-
-pub const BISHOP_TABLE: [u64; BISHOP_SIZE] = get_bishop_table();
-pub const ROOK_TABLE: [u64; ROOK_SIZE] = get_rook_table();
+pub const STARTPOS: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
 
 pub const PIECES: [Piece; 6] = [Piece::King, Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight, Piece::Pawn];
 
@@ -29,16 +27,20 @@ pub struct Board {
     pub enpassant: BitBoard,
     prev_enpassant: BitBoard, // store previous enpessant (undo move)
     pub halfmoves: u8,
+    prev_halfmoves: u8, // store previous halfmove clock (undo move)
     pub fullmoves: u16,
+    prev_fullmoves: u16, // store previous fullmove number (undo move)
     pub squares: [Option<Piece>; 64], // Current position piece lookup
-    prev_square: Option<Piece>
+    prev_square: Option<Piece>,
+    pub(crate) key: u64, // Zobrist key, kept up to date by apply_move
+    prev_key: u64 // store previous Zobrist key (undo move)
 }
 
 struct Masks {
     checkmask: BitBoard,
+    checkers: BitBoard,
     diagonal: BitBoard,
-    orthagonal: BitBoard,
-    en_pessant: BitBoard // Contains mask of "pinned" enemy pawns preventing illegal en pessant
+    orthagonal: BitBoard
 }
 #[derive(Debug, Error)]
 pub enum FENParseError {
@@ -99,12 +101,12 @@ impl std::str::FromStr for Board {
         board.turn = Color::from_str(color)?;
 
         if board.pieces[0][0] == BitBoard(16) {
-            board.castling[0].0 = castling.contains('K');
-            board.castling[0].1 = castling.contains('Q');
+            board.castling[0].0 = castling.contains('K') && !(board.pieces[0][2] & BitBoard(0x80)).is_empty();
+            board.castling[0].1 = castling.contains('Q') && !(board.pieces[0][2] & BitBoard(0x1)).is_empty();
         }
         if board.pieces[1][0] == BitBoard(0x1000000000000000) {
-            board.castling[1].0 = castling.contains('k');
-            board.castling[1].1 = castling.contains('q');
+            board.castling[1].0 = castling.contains('k') && !(board.pieces[1][2] & BitBoard(0x8000000000000000)).is_empty();
+            board.castling[1].1 = castling.contains('q') && !(board.pieces[1][2] & BitBoard(0x100000000000000)).is_empty();
         }
 
         if enpassant != "-" {
@@ -123,6 +125,7 @@ impl std::str::FromStr for Board {
 
         board.halfmoves = halfmoves.parse()?;
         board.fullmoves = fullmoves.parse()?;
+        board.key = board.compute_hash();
 
         Ok(board)
     }
@@ -131,7 +134,7 @@ impl std::str::FromStr for Board {
 impl Default for Board {
     fn default() -> Self {
         use std::str::FromStr;
-        Board::from_str("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").expect("Error parsing FEN")
+        Board::from_str(STARTPOS).expect("Error parsing FEN")
     }
 }
 
@@ -144,11 +147,15 @@ impl Board {
             enpassant: BitBoard::EMPTY,
             prev_enpassant: BitBoard::EMPTY,
             halfmoves: 0,
+            prev_halfmoves: 0,
             fullmoves: 0,
+            prev_fullmoves: 0,
             pieces: [[BitBoard::EMPTY; 6]; 2],
             turn: Color::White,
             squares: [None; 64],
-            prev_square: None
+            prev_square: None,
+            key: 0,
+            prev_key: 0
         }
     }
 
@@ -164,77 +171,139 @@ impl Board {
         self.all[1 - self.turn as usize]
     }
 
+    /// Returns the position in Forsyth-Edwards Notation
+    pub fn fen(&self) -> String {
+        let mut fen = String::new();
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                let sq = Square(8 * rank + file);
+                match self.squares[sq.0] {
+                    Some(piece) => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        let letter = piece.to_string();
+                        fen.push_str(&match (sq.bitboard() & self.all[0]).is_empty() {
+                            true => letter.to_lowercase(),
+                            false => letter
+                        });
+                    }
+                    None => empty += 1
+                }
+            }
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+            if rank > 0 {
+                fen.push('/');
+            }
+        }
+
+        fen.push_str(match self.turn {
+            Color::White => " w ",
+            Color::Black => " b "
+        });
+
+        let castling: String = [(self.castling[0].0, 'K'), (self.castling[0].1, 'Q'), (self.castling[1].0, 'k'), (self.castling[1].1, 'q')]
+            .into_iter()
+            .filter_map(|(allowed, c)| allowed.then_some(c))
+            .collect();
+        fen.push_str(if castling.is_empty() { "-" } else { &castling });
+
+        match self.enpassant.into_iter().next() {
+            Some(sq) => fen.push_str(&format!(" {sq}")),
+            None => fen.push_str(" -")
+        }
+
+        fen.push_str(&format!(" {} {}", self.halfmoves, self.fullmoves));
+        fen
+    }
+
+    /// Returns the square of the king of the side to move
+    pub const fn king(&self) -> Square {
+        Square(self.pieces[self.turn as usize][0].0.trailing_zeros() as usize)
+    }
+
+    /// Specifies whether the side to move is in check
+    pub fn in_check(&self) -> bool {
+        !self.masks().checkers.is_empty()
+    }
+
+    /// Returns the enemy pieces giving check to the side to move
+    pub fn checkers(&self) -> BitBoard {
+        self.masks().checkers
+    }
 
     /// Calculates checkmask and pinmask for current position and color
     fn masks(&self) -> Masks {
         let color = self.turn as usize;
-        let king = self.pieces[color][0].0.trailing_zeros() as usize;
-        // assert!(king < 64, "{:?}", self.pieces[color][0]);
+        let king = self.king();
         let enemy = self.pieces[1 - color];
 
-        let diagonal = BitBoard(BISHOP_ATTACKS[king]) & (enemy[1] | enemy[3]);
-        let orthagonal = BitBoard(ROOK_ATTACKS[king]) & (enemy[1] | enemy[2]);
+        let diagonal = BitBoard(BISHOP_ATTACKS[king.0]) & (enemy[1] | enemy[3]);
+        let orthagonal = BitBoard(ROOK_ATTACKS[king.0]) & (enemy[1] | enemy[2]);
 
-        let mut checkmask = BitBoard::EMPTY;
+        // Knights and pawns can only be captured, never blocked
+        let mut checkers = (BitBoard(KNIGHT_MOVES[king.0]) & enemy[4]) | (BitBoard(PAWN_ATTACKS[color][king.0]) & enemy[5]);
+        let mut checkmask = checkers;
         let mut pinmask = (BitBoard::EMPTY, BitBoard::EMPTY);
-        let mut en_pessant = BitBoard::EMPTY;
 
         for attacker in orthagonal {
-            let between = squares_between(Square(king), attacker);
-            if (between & self.occupied()).is_empty() {
+            let between = squares_between(king, attacker);
+            let blockers = between & self.occupied();
+            if blockers.is_empty() {
                 checkmask |= between | attacker.bitboard();
-            }
-            if (between & self.us()).len() == 1 {
+                checkers |= attacker.bitboard();
+            } else if blockers.len() == 1 && !(blockers & self.us()).is_empty() {
                 pinmask.0 |= between | attacker.bitboard();
             }
         }
 
         for attacker in diagonal {
-            let between = squares_between(Square(king), attacker);
-            if (between & self.occupied()).is_empty() {
+            let between = squares_between(king, attacker);
+            let blockers = between & self.occupied();
+            if blockers.is_empty() {
                 checkmask |= between | attacker.bitboard();
-            }
-            if (between & self.us()).len() == 1 && (between & self.them()).is_empty() {
+                checkers |= attacker.bitboard();
+            } else if blockers.len() == 1 && !(blockers & self.us()).is_empty() {
                 pinmask.1 |= between | attacker.bitboard();
             }
-            if (between & enemy[5]).len() == 1 {
-                en_pessant |= between & enemy[5];
-            }
         }
 
-        if checkmask.is_empty() {
+        if checkers.is_empty() {
             checkmask = BitBoard::FULL;
         }
 
         Masks {
             checkmask,
+            checkers,
             orthagonal: pinmask.0,
-            diagonal: pinmask.1,
-            en_pessant
+            diagonal: pinmask.1
         }
     }
 
     /// Returns mask containing all squares attacked by enemy pieces
     pub fn danger(&self) -> BitBoard {
-        let enemy_color = 1 - self.turn as usize;
-        let enemy = self.pieces[enemy_color];
+        let enemy = self.pieces[1 - self.turn as usize];
+        // Sliders see through our king, so it cannot step back along a checking ray
+        let occupied = self.occupied() ^ self.pieces[self.turn as usize][0];
 
         // pawns
-        let mut mask = BitBoard(((enemy[5] & BitBoard::NOT_A).0 >> 9 | (enemy[5] & BitBoard::NOT_H).0 >> 7) << (16 * enemy_color));
+        let mut mask = match self.turn {
+            Color::White => (enemy[5] & BitBoard::NOT_A).shr(9) | (enemy[5] & BitBoard::NOT_H).shr(7),
+            Color::Black => (enemy[5] & BitBoard::NOT_A).shl(7) | (enemy[5] & BitBoard::NOT_H).shl(9)
+        };
         // king
         mask |= BitBoard(KING_MOVES[enemy[0].0.trailing_zeros() as usize]);
         // bishops / queen diagonals
         for piece in enemy[1] | enemy[3] {
-            let blockers = BitBoard(BISHOP_BLOCKERS[piece.0]) & (self.occupied() ^ self.pieces[self.turn as usize][0]);
-            let idx = magic_index(blockers, BISHOP_MAGICS[piece.0], BISHOP_SHIFTS[piece.0]) + BISHOP_OFFSETS[piece.0];
-            mask |= BitBoard(BISHOP_TABLE[idx]);
+            mask |= bishop_attacks(piece, occupied);
         }
         // rooks / queen orthagonals
         for piece in enemy[1] | enemy[2] {
-            let blockers = BitBoard(ROOK_BLOCKERS[piece.0]) & (self.occupied() ^ self.pieces[self.turn as usize][0]);
-            // dbg!(piece, ROOK_BLOCKERS[piece.0], blockers);
-            let idx = magic_index(blockers, ROOK_MAGICS[piece.0], ROOK_SHIFTS[piece.0]) + ROOK_OFFSETS[piece.0];
-            mask |= BitBoard(ROOK_TABLE[idx]);
+            mask |= rook_attacks(piece, occupied);
         }
         // knights
         for knight in enemy[4] {
@@ -243,41 +312,147 @@ impl Board {
         mask
     }
 
-    // TODO: Compare nodes searched for known positions to find errors
-    /// Returns all moves for current position and color
-    pub fn moves(&self) -> Vec<PieceMoves> {
-        let Masks {checkmask, orthagonal, diagonal, en_pessant} = self.masks();
-        let mut moves = vec![];
+    /// Returns the pieces of both colours attacking `sq`, with sliders blocked by `occupied`
+    pub fn attackers_to(&self, sq: Square, occupied: BitBoard) -> BitBoard {
+        let [white, black] = self.pieces;
+        (BitBoard(PAWN_ATTACKS[Color::Black as usize][sq.0]) & white[5])
+            | (BitBoard(PAWN_ATTACKS[Color::White as usize][sq.0]) & black[5])
+            | (BitBoard(KNIGHT_MOVES[sq.0]) & (white[4] | black[4]))
+            | (BitBoard(KING_MOVES[sq.0]) & (white[0] | black[0]))
+            | (bishop_attacks(sq, occupied) & (white[1] | black[1] | white[3] | black[3]))
+            | (rook_attacks(sq, occupied) & (white[1] | black[1] | white[2] | black[2]))
+    }
+
+    /// Specifies whether the enemy attacks `sq`, with sliders blocked by `occupied`
+    ///
+    /// With the `king-attackers` feature move generation tests the king's destinations
+    /// and castling squares with this instead of building `danger()`, see the `king_safety` example.
+    pub fn attacked(&self, sq: Square, occupied: BitBoard) -> bool {
+        !(self.attackers_to(sq, occupied) & self.them()).is_empty()
+    }
+
+    /// Returns the king's legal destinations, castling included
+    #[cfg(not(feature = "king-attackers"))]
+    fn king_moves(&self, checkers: BitBoard) -> BitBoard {
         let color = self.turn as usize;
-        let pieces = self.pieces[color];
+        let occupied = self.occupied();
         let danger = self.danger();
+        let king = self.king();
+        let mut bb = BitBoard(KING_MOVES[king.0]) & !self.us() & !danger;
+        if checkers.is_empty() {
+            // Not in check => castling
+            let kingside = king.bitboard().shl(1) | king.bitboard().shl(2);
+            if self.castling[color].0 && ((occupied | danger) & kingside).is_empty() {
+                bb |= king.bitboard().shl(2);
+            }
+            let queenside = king.bitboard().shr(1) | king.bitboard().shr(2);
+            if self.castling[color].1 && ((occupied | danger) & queenside).is_empty() && (occupied & king.bitboard().shr(3)).is_empty() {
+                bb |= king.bitboard().shr(2);
+            }
+        }
+        bb
+    }
 
-        // King moves
-        let king = Square(self.pieces[color][0].0.trailing_zeros() as usize);
-        let mut bb = BitBoard(KING_MOVES[king.0]);
-        if (danger & king.bitboard()).is_empty() {
+    /// Returns the king's legal destinations, castling included, testing only the
+    /// squares the king could reach instead of every square the enemy attacks
+    #[cfg(feature = "king-attackers")]
+    fn king_moves(&self, checkers: BitBoard) -> BitBoard {
+        let color = self.turn as usize;
+        let occupied = self.occupied();
+        let king = self.king();
+        // Sliders see through our king, so it cannot step back along a checking ray
+        let without_king = occupied ^ king.bitboard();
+        let safe = |squares: BitBoard| squares.into_iter().all(|sq| !self.attacked(sq, without_king));
+        let mut bb = BitBoard::EMPTY;
+        for to in BitBoard(KING_MOVES[king.0]) & !self.us() {
+            if !self.attacked(to, without_king) {
+                bb |= to.bitboard();
+            }
+        }
+        if checkers.is_empty() {
             // Not in check => castling
-            if self.castling[color].0 && (danger & BitBoard(king.bitboard().0 << 1)).is_empty() {
-                bb |= BitBoard(king.bitboard().0 << 2);
+            let kingside = king.bitboard().shl(1) | king.bitboard().shl(2);
+            if self.castling[color].0 && (occupied & kingside).is_empty() && safe(kingside) {
+                bb |= king.bitboard().shl(2);
             }
-            if self.castling[color].1 && (danger & BitBoard(king.bitboard().0 >> 1)).is_empty() {
-                bb |= BitBoard(king.bitboard().0 >> 2);
+            let queenside = king.bitboard().shr(1) | king.bitboard().shr(2);
+            if self.castling[color].1 && (occupied & (queenside | king.bitboard().shr(3))).is_empty() && safe(queenside) {
+                bb |= king.bitboard().shr(2);
             }
         }
-        moves.push(PieceMoves {
+        bb
+    }
+
+    /// Returns the en-passant capture available to the pawn on `from`, if it is legal
+    fn enpassant_capture(&self, from: Square, checkmask: BitBoard) -> BitBoard {
+        let target = BitBoard(PAWN_ATTACKS[self.turn as usize][from.0]) & self.enpassant;
+        if target.is_empty() {
+            return BitBoard::EMPTY;
+        }
+        let captured = target.shift_color(8, !self.turn);
+        // Capturing the checking pawn is fine even though the target square is not on the checkmask
+        if (checkmask & (target | captured)).is_empty() {
+            return BitBoard::EMPTY;
+        }
+        // Two pawns leave the board at once, so pins can't catch every discovered attack
+        let occupied = (self.occupied() ^ from.bitboard() ^ captured) | target;
+        let king = self.king();
+        let enemy = self.pieces[1 - self.turn as usize];
+        if !(rook_attacks(king, occupied) & (enemy[1] | enemy[2])).is_empty()
+            || !(bishop_attacks(king, occupied) & (enemy[1] | enemy[3])).is_empty() {
+            return BitBoard::EMPTY;
+        }
+        target
+    }
+
+    /// Returns all moves for current position and color
+    pub fn moves(&self) -> MoveList {
+        let mut moves = MoveList::new();
+        let them = self.them();
+        self.generate(|piece_moves| piece_moves.extend(&mut moves, self.turn, them, self.enpassant));
+        moves
+    }
+
+    /// Counts the legal moves without listing them, promotions count four times
+    pub fn count_moves(&self) -> u64 {
+        let mut count = 0;
+        self.generate(|piece_moves| count += piece_moves.len() as u64);
+        count
+    }
+
+    /// Returns all moves for current position and color, grouped per piece
+    pub fn piece_moves(&self) -> Vec<PieceMoves> {
+        let mut moves = vec![];
+        self.generate(|piece_moves| moves.push(piece_moves));
+        moves
+    }
+
+    /// Generates the legal moves of every piece and passes them to `emit`
+    fn generate<F: FnMut(PieceMoves)>(&self, mut emit: F) {
+        let Masks {checkmask, checkers, orthagonal, diagonal} = self.masks();
+        let color = self.turn as usize;
+        let pieces = self.pieces[color];
+        let occupied = self.occupied();
+
+        // King moves
+        let king = self.king();
+        let bb = self.king_moves(checkers);
+        emit(PieceMoves {
             piece: Piece::King,
             from: king,
-            moves: bb & !self.us() & !danger
+            moves: bb
         });
 
-        if (checkmask & self.them()).len() > 1 && checkmask != BitBoard::FULL {
+        if checkers.len() > 1 {
             // Double check, only generate king moves
-            return moves;
+            return;
         }
 
-        let knights = pieces[4] & !(orthagonal | diagonal);
+        let pinned = orthagonal | diagonal;
+
+        let knights = pieces[4] & !pinned;
         for from in knights {
-            moves.push(PieceMoves {
+            emit(PieceMoves {
                 piece: Piece::Knight,
                 from,
                 moves: BitBoard(KNIGHT_MOVES[from.0]) & checkmask & !self.us()
@@ -285,201 +460,464 @@ impl Board {
         }
 
         // Pinless rook + queen moves
-        let rooks = (pieces[1] | pieces[2]) & !(orthagonal | diagonal);
+        let rooks = (pieces[1] | pieces[2]) & !pinned;
         for from in rooks {
-            let blockers = BitBoard(ROOK_BLOCKERS[from.0]) & self.occupied();
-            let idx = magic_index(blockers, ROOK_MAGICS[from.0], ROOK_SHIFTS[from.0]) + ROOK_OFFSETS[from.0];
             let piece = match (from.bitboard() & pieces[1]).is_empty() {
                 true => Piece::Rook,
                 false => Piece::Queen
             };
-            moves.push(PieceMoves {
+            emit(PieceMoves {
                 piece,
                 from,
-                moves: BitBoard(ROOK_TABLE[idx]) & checkmask & !self.us()
+                moves: rook_attacks(from, occupied) & checkmask & !self.us()
             });
         }
 
         // Pinless bishop + queen moves
-        let bishops = (pieces[1] | pieces[3]) & !(orthagonal | diagonal);
+        let bishops = (pieces[1] | pieces[3]) & !pinned;
         for from in bishops {
-            let blockers = BitBoard(BISHOP_BLOCKERS[from.0]) & self.occupied();
-            let idx = magic_index(blockers, BISHOP_MAGICS[from.0], BISHOP_SHIFTS[from.0]) + BISHOP_OFFSETS[from.0];
             let piece = match (from.bitboard() & pieces[1]).is_empty() {
                 true => Piece::Bishop,
                 false => Piece::Queen
             };
-            moves.push(PieceMoves {
+            emit(PieceMoves {
                 piece,
                 from,
-                moves: BitBoard(BISHOP_TABLE[idx]) & checkmask & !self.us()
+                moves: bishop_attacks(from, occupied) & checkmask & !self.us()
             });
         }
 
         // Diagonally pinned queens and bishops
-        let diagonal = (pieces[1] | pieces[3]) & diagonal;
-        for from in diagonal {
-            let blockers = BitBoard(BISHOP_BLOCKERS[from.0]) & self.occupied();
-            let idx = magic_index(blockers, BISHOP_MAGICS[from.0], BISHOP_SHIFTS[from.0]) + BISHOP_OFFSETS[from.0];
+        for from in (pieces[1] | pieces[3]) & diagonal {
             let piece = match (from.bitboard() & pieces[1]).is_empty() {
                 true => Piece::Bishop,
                 false => Piece::Queen
             };
-            moves.push(PieceMoves {
+            emit(PieceMoves {
                 piece,
                 from,
-                moves: BitBoard(BISHOP_TABLE[idx]) & checkmask & !self.us() & diagonal
+                moves: bishop_attacks(from, occupied) & checkmask & !self.us() & diagonal
             });
         }
 
         // Orthagonally pinned queens and rooks
-        let orthagonal = (pieces[1] | pieces[2]) & orthagonal;
-        for from in orthagonal {
-            let blockers = BitBoard(ROOK_BLOCKERS[from.0]) & self.occupied();
-            let idx = magic_index(blockers, ROOK_MAGICS[from.0], ROOK_SHIFTS[from.0]) + ROOK_OFFSETS[from.0];
+        for from in (pieces[1] | pieces[2]) & orthagonal {
             let piece = match (from.bitboard() & pieces[1]).is_empty() {
                 true => Piece::Rook,
                 false => Piece::Queen
             };
-            moves.push(PieceMoves {
+            emit(PieceMoves {
                 piece,
                 from,
-                moves: BitBoard(ROOK_TABLE[idx]) & checkmask & !self.us() & orthagonal
+                moves: rook_attacks(from, occupied) & checkmask & !self.us() & orthagonal
             });
         }
 
-        let second_rank = match self.turn {
-            Color::White => BitBoard(0xff00),
-            Color::Black => BitBoard(0xff000000000000)
+        let third_rank = match self.turn {
+            Color::White => BitBoard(0xff0000),
+            Color::Black => BitBoard(0xff0000000000)
         };
 
-        // Orthagonally pinned pawns
-        // A pinned pawn cannot promote
-        let pawns = pieces[5] & orthagonal;
-        for from in pawns {
-            let mut bb = from.bitboard().shift_color(8, self.turn) & !self.occupied() & orthagonal;
-            if !(second_rank & from.bitboard()).is_empty() && !bb.is_empty() {
-                bb |= bb.shift_color(8, self.turn);
-            }
-            moves.push(PieceMoves {
+        // Orthagonally pinned pawns can only push along the pin
+        for from in pieces[5] & orthagonal {
+            let single = from.bitboard().shift_color(8, self.turn) & !occupied;
+            let double = (single & third_rank).shift_color(8, self.turn) & !occupied;
+            emit(PieceMoves {
                 piece: Piece::Pawn,
                 from,
-                moves: bb & checkmask
+                moves: (single | double) & checkmask & orthagonal
             });
         }
 
-        // Diagonally pinned pawns
-        let pawns = pieces[5] & diagonal;
-        for from in pawns {
-            moves.push(PieceMoves {
+        // Diagonally pinned pawns can only capture along the pin
+        for from in pieces[5] & diagonal {
+            emit(PieceMoves {
                 piece: Piece::Pawn,
                 from,
-                moves: BitBoard(PAWN_ATTACKS[color][from.0]) & checkmask & diagonal & self.all[1 - color]
+                moves: (BitBoard(PAWN_ATTACKS[color][from.0]) & checkmask & diagonal & self.them()) | self.enpassant_capture(from, checkmask)
             });
         }
 
         // Pinless pawn pushes and captures
-        let pawns = pieces[5] & !(orthagonal | diagonal);
-        for from in pawns {
+        for from in pieces[5] & !pinned {
             // captures
-            let mut bb = BitBoard(PAWN_ATTACKS[color][from.0]) & (self.all[1 - color] | (self.enpassant.shift_color(8, self.turn) & !en_pessant));
+            let mut bb = BitBoard(PAWN_ATTACKS[color][from.0]) & self.them();
             // single push
-            let single = from.bitboard().shift_color(8, self.turn) & !self.occupied();
+            let single = from.bitboard().shift_color(8, self.turn) & !occupied;
             bb |= single;
             // double push
-            if !single.is_empty() {
-                bb |= single.shift_color(8, self.turn) & !self.occupied();
-            }
+            bb |= (single & third_rank).shift_color(8, self.turn) & !occupied;
 
-            moves.push(PieceMoves {
+            emit(PieceMoves {
                 piece: Piece::Pawn,
                 from,
-                moves: bb & checkmask
+                moves: (bb & checkmask) | self.enpassant_capture(from, checkmask)
             });
         }
+    }
 
-        moves
+    /// Returns the piece standing on `sq`, if any
+    pub const fn piece_on(&self, sq: Square) -> Option<Piece> {
+        self.squares[sq.0]
+    }
+
+    /// Returns the piece moved by `mv` in the current position
+    pub fn moved_piece(&self, mv: &Move) -> Option<Piece> {
+        self.squares[mv.from().0]
+    }
+
+    /// Returns the piece captured by `mv` in the current position
+    pub fn captured_piece(&self, mv: &Move) -> Option<Piece> {
+        match mv.kind() {
+            MoveKind::EnPassant => Some(Piece::Pawn),
+            _ => self.squares[mv.to().0]
+        }
+    }
+
+    /// Moves the rook belonging to a castling move
+    fn castle_rook(&mut self, mv: &Move, color: usize) {
+        let (from, to) = match mv.kind() {
+            MoveKind::KingCastle => (mv.to().0 + 1, mv.to().0 - 1),
+            _ => (mv.to().0 - 2, mv.to().0 + 1)
+        };
+        let from_to = Square(from).bitboard() | Square(to).bitboard();
+        self.pieces[color][2] ^= from_to;
+        self.all[color] ^= from_to;
+        self.squares.swap(from, to);
+        self.key ^= ZOBRIST.pieces[color][2][from] ^ ZOBRIST.pieces[color][2][to];
     }
 
     /// Applies given move to current position
     pub fn apply_move(&mut self, mv: &Move) {
-        // println!("{}{}", mv.from, mv.to);
-        let piece = mv.piece as usize;
         let color = self.turn as usize;
+        let (from, to) = (mv.from(), mv.to());
+        let from_to = from.bitboard() | to.bitboard();
+        let piece = self.squares[from.0].expect("no piece to move");
+
+        self.prev_castling = self.castling;
+        self.prev_enpassant = self.enpassant;
+        self.prev_halfmoves = self.halfmoves;
+        self.prev_fullmoves = self.fullmoves;
+        self.prev_square = self.squares[to.0];
+        self.prev_key = self.key;
+
+        // Rights and en passant are hashed again once the move is made
+        self.key ^= self.castling_key() ^ self.enpassant_key();
+        self.enpassant = BitBoard::EMPTY;
+        // A FEN may start the clock at its maximum
+        self.halfmoves = self.halfmoves.saturating_add(1);
+
+        if let Some(captured) = self.prev_square {
+            self.pieces[1 - color][captured as usize] ^= to.bitboard();
+            self.all[1 - color] ^= to.bitboard();
+            self.key ^= ZOBRIST.pieces[1 - color][captured as usize][to.0];
+            self.halfmoves = 0;
+        }
+
+        self.pieces[color][piece as usize] ^= from_to;
+        self.all[color] ^= from_to;
+        self.squares[from.0] = None;
+        self.squares[to.0] = Some(piece);
+        self.key ^= ZOBRIST.pieces[color][piece as usize][from.0] ^ ZOBRIST.pieces[color][piece as usize][to.0];
 
-        self.pieces[color][piece] ^= mv.from.bitboard() | mv.to.bitboard();
-        self.all[color] ^= mv.from.bitboard() | mv.to.bitboard();
-        self.all[1 - color] &= !mv.to.bitboard();
-        if let Some(p) = self.squares[mv.to.0] {
-            // we captured a piece, but we don't know whose piece it is!
-            self.pieces[0][p as usize] &= !mv.to.bitboard();
-            self.pieces[1][p as usize] &= !mv.to.bitboard();
-        }
-        self.squares[mv.from.0] = None;
-        self.prev_square = self.squares[mv.to.0];
-        self.squares[mv.to.0] = Some(mv.piece);
-
-        match mv.piece {
-            Piece::King => {
-                self.prev_castling = self.castling;
-                self.castling[color] = (false, false);
+        match mv.kind() {
+            MoveKind::DoublePush => {
+                self.enpassant = from.bitboard().shift_color(8, self.turn);
             }
-            Piece::Pawn => {
-                // Promotion
-                if let Some(flags) = &mv.flags {
-                    let Promotion(promotion) = flags;
-                    self.pieces[color][piece] ^= mv.from.bitboard();
-                    self.squares[mv.to.0] = match *promotion {
-                        PromotionPiece::Queen => Some(Piece::Queen),
-                        PromotionPiece::Rook => Some(Piece::Rook),
-                        PromotionPiece::Bishop => Some(Piece::Bishop),
-                        PromotionPiece::Knight => Some(Piece::Knight)
-                    };
-                    self.pieces[color][*promotion as usize + 1] ^= mv.to.bitboard();
-                }
-                // En pessant
-                self.prev_enpassant = self.enpassant;
-                self.enpassant |= (mv.from.bitboard().shift_color(16, self.turn) & mv.to.bitboard()).shift_color(8, !self.turn);
+            MoveKind::EnPassant => {
+                let captured = to.bitboard().shift_color(8, !self.turn);
+                self.pieces[1 - color][5] ^= captured;
+                self.all[1 - color] ^= captured;
+                self.squares[captured.0.trailing_zeros() as usize] = None;
+                self.key ^= ZOBRIST.pieces[1 - color][5][captured.0.trailing_zeros() as usize];
             }
-            Piece::Rook => {
-                // Kingside rook
-                self.prev_castling = self.castling;
-                if (self.pieces[color][0].shl(3) & self.pieces[color][2]).is_empty() {
-                    self.castling[color].0 = false;
-                }
-                // Queenside rook
-                if (self.pieces[color][0].shr(4) & self.pieces[color][2]).is_empty() {
-                    self.castling[color].1 = false;
-                }
+            MoveKind::KingCastle | MoveKind::QueenCastle => self.castle_rook(mv, color),
+            kind => if let Some(promotion) = kind.promotion_piece() {
+                let promotion = Piece::from(promotion);
+                self.pieces[color][5] ^= to.bitboard();
+                self.pieces[color][promotion as usize] ^= to.bitboard();
+                self.squares[to.0] = Some(promotion);
+                self.key ^= ZOBRIST.pieces[color][5][to.0] ^ ZOBRIST.pieces[color][promotion as usize][to.0];
             }
-            _ => {}
         }
 
+        if piece == Piece::Pawn {
+            self.halfmoves = 0;
+        }
+
+        // Moving to or from a king or rook square revokes the matching rights
+        for side in 0..2 {
+            let rank = 56 * side;
+            if !(from_to & Square(rank + 4).bitboard()).is_empty() {
+                self.castling[side] = (false, false);
+            }
+            if !(from_to & Square(rank + 7).bitboard()).is_empty() {
+                self.castling[side].0 = false;
+            }
+            if !(from_to & Square(rank).bitboard()).is_empty() {
+                self.castling[side].1 = false;
+            }
+        }
+
+        if self.turn == Color::Black {
+            self.fullmoves = self.fullmoves.saturating_add(1);
+        }
         self.turn = !self.turn;
-        assert!(!self.pieces[0][0].is_empty(), "white king captured!");
+        self.key ^= ZOBRIST.black ^ self.castling_key() ^ self.enpassant_key();
     }
 
-    /// undo move to position
+    /// Undoes the move that was just applied
+    ///
+    /// Only the previous state is stored, so moves must be undone in reverse
+    /// order directly after applying them.
     pub fn undo_move(&mut self, mv: &Move) {
-        let piece = mv.piece as usize;
+        self.turn = !self.turn;
         let color = self.turn as usize;
+        let (from, to) = (mv.from(), mv.to());
+        let from_to = from.bitboard() | to.bitboard();
+
+        let mut piece = self.squares[to.0].expect("no piece to move back");
+        if mv.is_promotion() {
+            self.pieces[color][piece as usize] ^= to.bitboard();
+            self.pieces[color][5] ^= to.bitboard();
+            piece = Piece::Pawn;
+        }
+
+        self.pieces[color][piece as usize] ^= from_to;
+        self.all[color] ^= from_to;
+        self.squares[from.0] = Some(piece);
+        self.squares[to.0] = self.prev_square;
 
-        self.squares[mv.to.0] = self.prev_square;
-        self.squares[mv.from.0] = Some(mv.piece);
-        self.all[1 - color] ^= mv.from.bitboard() | mv.to.bitboard();
-        self.all[color] |= if self.prev_square.is_some() { mv.to.bitboard() } else { BitBoard::EMPTY };
+        if let Some(captured) = self.prev_square {
+            self.pieces[1 - color][captured as usize] ^= to.bitboard();
+            self.all[1 - color] ^= to.bitboard();
+        }
+
+        match mv.kind() {
+            MoveKind::EnPassant => {
+                let captured = to.bitboard().shift_color(8, !self.turn);
+                self.pieces[1 - color][5] ^= captured;
+                self.all[1 - color] ^= captured;
+                self.squares[captured.0.trailing_zeros() as usize] = Some(Piece::Pawn);
+            }
+            MoveKind::KingCastle | MoveKind::QueenCastle => self.castle_rook(mv, color),
+            _ => {}
+        }
 
         self.castling = self.prev_castling;
         self.enpassant = self.prev_enpassant;
+        self.halfmoves = self.prev_halfmoves;
+        self.fullmoves = self.prev_fullmoves;
+        self.key = self.prev_key;
+    }
+}
+
+/// Rook attacks from `sq` given the `occupied` squares
+pub fn rook_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+    Backend::rook(sq, occupied)
+}
+
+/// Bishop attacks from `sq` given the `occupied` squares
+pub fn bishop_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+    Backend::bishop(sq, occupied)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use types::piece::Piece;
+    use types::moves::Move;
+    use types::square::Square;
+
+    use crate::board::Board;
+
+    fn count(fen: &str) -> usize {
+        Board::from_str(fen).unwrap().moves().len()
+    }
 
-        if let Some(flags) = &mv.flags {
-            let Promotion(promotion) = flags;
-            self.pieces[1 - color][piece] ^= mv.from.bitboard();
-            self.pieces[1 - color][*promotion as usize] ^= mv.to.bitboard();
-        } else {
-            self.pieces[1 - color][piece] ^= mv.from.bitboard() | mv.to.bitboard();
+    fn has_move(fen: &str, uci: &str) -> bool {
+        Board::from_str(fen).unwrap().moves().iter().any(|mv| mv.to_string() == uci)
+    }
+
+    fn find(board: &Board, uci: &str) -> Move {
+        *board.moves().iter().find(|mv| mv.to_string() == uci).unwrap()
+    }
+
+    #[test]
+    fn test_checkers() {
+        // Knight and pawn checks can't be blocked, so only the king may move
+        assert_eq!(count("4k3/8/8/8/8/5n2/8/R3K3 w - - 0 1"), 4);
+        assert_eq!(count("4k3/8/8/8/8/8/3p4/R3K3 w - - 0 1"), 5);
+        // Rook and knight give double check, the rook on a2 may not block
+        assert_eq!(count("4r1k1/8/8/8/8/3n4/R7/4K3 w - - 0 1"), 3);
+        assert!(Board::from_str("4k3/8/8/8/8/5n2/8/4K3 w - - 0 1").unwrap().in_check());
+        assert!(!Board::default().in_check());
+    }
+
+    #[test]
+    fn test_pawn_danger() {
+        // Enemy pawns attack towards our side of the board
+        assert_eq!(count("4k3/8/8/8/8/3p4/8/4K3 w - - 0 1"), 4);
+        assert_eq!(count("4k3/8/3P4/8/8/8/8/4K3 b - - 0 1"), 4);
+        // The king can't step back along the ray of a checking rook
+        assert_eq!(count("4k3/4r3/8/8/4K3/8/8/8 w - - 0 1"), 6);
+    }
+
+    #[test]
+    fn test_attackers_to() {
+        let board = Board::from_str("4k3/4r3/8/8/8/3p4/8/4K3 w - - 0 1").unwrap();
+        let e2 = Square(12);
+        let attackers = [Square(4), Square(19), Square(52)].map(|sq| sq.bitboard());
+        assert_eq!(board.attackers_to(e2, board.occupied()), attackers[0] | attackers[1] | attackers[2]);
+        // Sliders are blocked by whatever `occupied` holds, leapers are not
+        assert_eq!(board.attackers_to(e2, board.occupied() | Square(28).bitboard()), attackers[0] | attackers[1]);
+        assert!(board.attacked(e2, board.occupied()));
+        assert!(!board.attacked(Square(3), board.occupied()));
+    }
+
+    #[test]
+    fn test_castling() {
+        assert!(has_move("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1", "e1g1"));
+        assert!(has_move("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1", "e1c1"));
+        // Every square between king and rook has to be empty
+        assert!(!has_move("4k3/8/8/8/8/8/8/R3KB1R w KQ - 0 1", "e1g1"));
+        assert!(!has_move("4k3/8/8/8/8/8/8/RN2K2R w KQ - 0 1", "e1c1"));
+        // The king may not pass through an attacked square
+        assert!(!has_move("4k3/8/8/8/8/8/5r2/R3K2R w KQ - 0 1", "e1g1"));
+        // Rights without a rook in the corner are dropped
+        assert!(!has_move("4k3/8/8/8/8/8/8/4K2R w KQ - 0 1", "e1c1"));
+        assert_eq!(Board::from_str("4k3/8/8/8/8/8/8/4K2R w KQ - 0 1").unwrap().castling[0], (true, false));
+    }
+
+    #[test]
+    fn test_enpassant() {
+        assert!(has_move("4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 1", "d5e6"));
+        // Both pawns leave the rank, exposing the king to the rook
+        assert!(!has_move("8/8/8/KPp4r/8/8/8/4k3 w - c6 0 1", "b5c6"));
+        // Taking the checking pawn en passant evades the check
+        assert!(has_move("8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1", "e4d3"));
+        // A diagonally pinned pawn may capture en passant along the pin
+        assert!(has_move("4k3/6b1/8/4Pp2/8/2K5/8/8 w - f6 0 1", "e5f6"));
+    }
+
+    #[test]
+    fn test_pawn_pushes() {
+        // Only pawns on their starting rank may push twice
+        assert_eq!(count("4k3/8/8/8/8/4P3/8/4K3 w - - 0 1"), 6);
+        assert!(has_move("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1", "e2e4"));
+        // Neither may push onto an occupied square, even when pinned
+        assert!(!has_move("4k3/8/8/8/4n3/8/4P3/4K3 w - - 0 1", "e2e4"));
+        assert!(!has_move("4k3/8/8/8/4r3/8/4P3/4K3 w - - 0 1", "e2e4"));
+    }
+
+    #[test]
+    fn test_apply_undo() {
+        let fens = [
+            "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1",
+            "4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 1",
+            "r3k3/1P6/8/8/8/8/8/4K3 w q - 0 1",
+        ];
+        for fen in fens {
+            let mut board = Board::from_str(fen).unwrap();
+            let before = board;
+            for mv in board.moves() {
+                board.apply_move(&mv);
+                board.undo_move(&mv);
+                assert_eq!(board.pieces, before.pieces, "{fen} {mv}");
+                assert_eq!(board.all, before.all, "{fen} {mv}");
+                assert_eq!(board.squares, before.squares, "{fen} {mv}");
+                assert_eq!(board.castling, before.castling, "{fen} {mv}");
+                assert_eq!(board.enpassant, before.enpassant, "{fen} {mv}");
+                assert_eq!((board.halfmoves, board.fullmoves), (before.halfmoves, before.fullmoves), "{fen} {mv}");
+            }
         }
+    }
 
-        self.turn = !self.turn;
+    #[test]
+    fn test_special_moves() {
+        // Castling moves the rook as well
+        let mut board = Board::from_str("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        board.apply_move(&find(&board, "e1g1"));
+        assert_eq!(board.piece_on(Square(5)), Some(Piece::Rook));
+        assert_eq!(board.piece_on(Square(7)), None);
+        assert_eq!(board.castling, [(false, false), (true, true)]);
+
+        // Capturing a rook on its corner revokes the matching right
+        let mut board = Board::from_str("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        board.apply_move(&find(&board, "a1a8"));
+        assert_eq!(board.castling, [(true, false), (true, false)]);
+
+        // The pawn taken en passant leaves the board
+        let mut board = Board::from_str("4k3/8/8/3Pp3/8/8/8/4K3 w - e6 0 1").unwrap();
+        board.apply_move(&find(&board, "d5e6"));
+        assert_eq!(board.piece_on(Square(36)), None);
+        assert!(board.pieces[1][5].is_empty());
+    }
+
+    #[test]
+    fn test_clocks() {
+        let mut board = Board::default();
+        for (uci, halfmoves, fullmoves) in [("g1f3", 1, 1), ("g8f6", 2, 2), ("e2e4", 0, 2), ("f6e4", 0, 3)] {
+            board.apply_move(&find(&board, uci));
+            assert_eq!((board.halfmoves, board.fullmoves), (halfmoves, fullmoves), "{uci}");
+        }
+    }
+
+    #[test]
+    fn test_halfmove_clock() {
+        let mut board = Board::from_str("4k3/8/8/8/8/8/8/4K3 w - - 255 80").unwrap();
+        let mv = board.parse_uci("e1e2").unwrap();
+        board.apply_move(&mv);
+        assert_eq!(board.halfmoves, 255);
+        board.undo_move(&mv);
+        assert_eq!(board.halfmoves, 255);
+    }
+
+    #[test]
+    fn test_fullmove_number() {
+        let mut board = Board::from_str("4k3/8/8/8/8/8/8/4K3 b - - 0 65535").unwrap();
+        let mv = board.parse_uci("e8e7").unwrap();
+        board.apply_move(&mv);
+        assert_eq!(board.fullmoves, 65535);
+        board.undo_move(&mv);
+        assert_eq!(board.fullmoves, 65535);
+    }
+
+    #[test]
+    fn test_perft() {
+        // Only the last move can be undone, so every node works on its own copy
+        fn perft(board: &Board, depth: usize) -> usize {
+            if depth == 0 {
+                return 1;
+            }
+            let mut board = *board;
+            let mut nodes = 0;
+            for mv in board.moves() {
+                board.apply_move(&mv);
+                nodes += perft(&board, depth - 1);
+                board.undo_move(&mv);
+            }
+            nodes
+        }
+
+        let positions = [
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", 3, 8902),
+            ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", 3, 97862),
+            ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 4, 43238),
+            ("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1", 3, 9467),
+            ("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8", 3, 62379),
+        ];
+        for (fen, depth, nodes) in positions {
+            assert_eq!(perft(&Board::from_str(fen).unwrap(), depth), nodes, "{fen}");
+        }
+    }
+
+    #[test]
+    fn test_pins() {
+        // An enemy pawn also stands between king and rook, so the knight is free
+        assert_eq!(count("4r1k1/8/8/8/4p3/8/4N3/4K3 w - - 0 1"), 10);
+        // A pinned bishop may still move along the pin
+        assert_eq!(count("4k3/8/8/8/b7/8/2B5/3K4 w - - 0 1"), 6);
     }
 }
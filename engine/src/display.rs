@@ -0,0 +1,220 @@
+use types::bitboard::BitBoard;
+use types::color::Color;
+use types::moves::Move;
+use types::piece::PieceLetters;
+use types::square::Square;
+
+use crate::board::Board;
+
+/// Unicode glyphs of Black's pieces, indexed like `Piece`
+const BLACK_FIGURINES: PieceLetters = PieceLetters(["\u{265a}", "\u{265b}", "\u{265c}", "\u{265d}", "\u{265e}", "\u{265f}"]);
+
+const LIGHT_SQUARE: &str = "\x1b[48;5;180m";
+const DARK_SQUARE: &str = "\x1b[48;5;137m";
+const HIGHLIGHT_SQUARE: &str = "\x1b[48;5;167m";
+const LAST_MOVE_SQUARE: &str = "\x1b[48;5;143m";
+const WHITE_PIECE: &str = "\x1b[1;97m";
+const BLACK_PIECE: &str = "\x1b[1;30m";
+const RESET: &str = "\x1b[0m";
+
+/// Configurable diagram of a board, created with `Board::display`
+///
+/// Without colours, highlighted squares are drawn as `[x]` and the squares
+/// of the last move as `(x)`.
+#[derive(Clone, Copy, Debug)]
+pub struct BoardDisplay<'a> {
+    board: &'a Board,
+    unicode: bool,
+    colors: bool,
+    flipped: bool,
+    summary: bool,
+    last_move: Option<Move>,
+    highlight: BitBoard,
+}
+
+impl Board {
+    /// Returns a diagram of the position that can be customised before printing
+    pub fn display(&self) -> BoardDisplay<'_> {
+        BoardDisplay {
+            board: self,
+            unicode: false,
+            colors: false,
+            flipped: false,
+            summary: true,
+            last_move: None,
+            highlight: BitBoard::EMPTY,
+        }
+    }
+}
+
+impl BoardDisplay<'_> {
+    /// Draws pieces as Unicode chess symbols instead of FEN letters
+    pub fn unicode(mut self, unicode: bool) -> Self {
+        self.unicode = unicode;
+        self
+    }
+
+    /// Colours squares and pieces with ANSI escape codes
+    pub fn colors(mut self, colors: bool) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Draws the board from Black's side
+    pub fn flipped(mut self, flipped: bool) -> Self {
+        self.flipped = flipped;
+        self
+    }
+
+    /// Prints the side to move, castling rights, en-passant square, clocks and FEN below the board
+    pub fn summary(mut self, summary: bool) -> Self {
+        self.summary = summary;
+        self
+    }
+
+    /// Marks the origin and destination of a move
+    pub fn last_move(mut self, mv: Move) -> Self {
+        self.last_move = Some(mv);
+        self
+    }
+
+    /// Marks every square of a bitboard, e.g. `danger()` or a checkmask
+    pub fn highlight(mut self, squares: BitBoard) -> Self {
+        self.highlight = squares;
+        self
+    }
+
+    fn glyph(&self, sq: Square) -> String {
+        let white = !(sq.bitboard() & self.board.all[Color::White as usize]).is_empty();
+        match self.board.squares[sq.0] {
+            Some(piece) if self.unicode && (white || self.colors) => PieceLetters::FIGURINE.get(piece).to_string(),
+            Some(piece) if self.unicode => BLACK_FIGURINES.get(piece).to_string(),
+            Some(piece) if white => piece.to_string(),
+            Some(piece) => piece.to_string().to_lowercase(),
+            None => String::from(if self.colors { " " } else { "." })
+        }
+    }
+
+    fn last_move_squares(&self) -> BitBoard {
+        match self.last_move {
+            Some(mv) => mv.from().bitboard() | mv.to().bitboard(),
+            None => BitBoard::EMPTY
+        }
+    }
+
+    fn square(&self, sq: Square) -> String {
+        let glyph = self.glyph(sq);
+        let highlighted = !(sq.bitboard() & self.highlight).is_empty();
+        let last_move = !(sq.bitboard() & self.last_move_squares()).is_empty();
+        if !self.colors {
+            return match (highlighted, last_move) {
+                (true, _) => format!("[{glyph}]"),
+                (false, true) => format!("({glyph})"),
+                (false, false) => format!(" {glyph} ")
+            };
+        }
+
+        let background = match (highlighted, last_move) {
+            (true, _) => HIGHLIGHT_SQUARE,
+            (false, true) => LAST_MOVE_SQUARE,
+            _ if (sq.0 / 8 + sq.0 % 8).is_multiple_of(2) => DARK_SQUARE,
+            _ => LIGHT_SQUARE
+        };
+        let foreground = match (sq.bitboard() & self.board.all[Color::White as usize]).is_empty() {
+            true => BLACK_PIECE,
+            false => WHITE_PIECE
+        };
+        format!("{background}{foreground} {glyph} {RESET}")
+    }
+
+    fn files(&self) -> String {
+        let files: Vec<String> = match self.flipped {
+            true => ('a'..='h').rev().map(|file| format!(" {file} ")).collect(),
+            false => ('a'..='h').map(|file| format!(" {file} ")).collect()
+        };
+        format!("  {}", files.concat()).trim_end().to_string()
+    }
+}
+
+impl std::fmt::Display for BoardDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.files())?;
+        for row in 0..8 {
+            let rank = if self.flipped { row } else { 7 - row };
+            write!(f, "{} ", rank + 1)?;
+            for col in 0..8 {
+                let file = if self.flipped { 7 - col } else { col };
+                write!(f, "{}", self.square(Square(8 * rank + file)))?;
+            }
+            writeln!(f, " {}", rank + 1)?;
+        }
+        writeln!(f, "{}", self.files())?;
+
+        if self.summary {
+            let fen = self.board.fen();
+            let fields: Vec<&str> = fen.split_whitespace().collect();
+            let turn = match self.board.turn {
+                Color::White => "White",
+                Color::Black => "Black"
+            };
+            writeln!(f)?;
+            writeln!(f, "{turn} to move, castling {}, en passant {}, halfmove clock {}, move {}",
+                fields[2], fields[3], self.board.halfmoves, self.board.fullmoves)?;
+            writeln!(f, "FEN: {fen}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints the position as an ASCII diagram followed by a summary of its state
+impl std::fmt::Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use types::square::Square;
+
+    use crate::board::Board;
+
+    #[test]
+    fn test_board_display() {
+        let mut board = Board::from_str("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        let mv = board.parse_uci("e2e4").unwrap();
+        board.apply_move(&mv);
+        assert_eq!(format!("\n{board}"), "
+   a  b  c  d  e  f  g  h
+8  r  n  b  q  k  b  n  r  8
+7  p  p  p  p  p  p  p  p  7
+6  .  .  .  .  .  .  .  .  6
+5  .  .  .  .  .  .  .  .  5
+4  .  .  .  .  P  .  .  .  4
+3  .  .  .  .  .  .  .  .  3
+2  P  P  P  P  .  P  P  P  2
+1  R  N  B  Q  K  B  N  R  1
+   a  b  c  d  e  f  g  h
+
+Black to move, castling KQkq, en passant e3, halfmove clock 0, move 1
+FEN: rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1
+");
+
+        let diagram = board.display()
+            .unicode(true)
+            .flipped(true)
+            .summary(false)
+            .last_move(mv)
+            .highlight(Square::from_str("d5").unwrap().bitboard())
+            .to_string();
+        assert_eq!(diagram.lines().nth(1), Some("1  \u{2656}  \u{2658}  \u{2657}  \u{2654}  \u{2655}  \u{2657}  \u{2658}  \u{2656}  1"));
+        assert_eq!(diagram.lines().nth(4), Some("4  .  .  . (\u{2659}) .  .  .  .  4"));
+        assert_eq!(diagram.lines().nth(5), Some("5  .  .  .  . [.] .  .  .  5"));
+        assert_eq!(diagram.lines().nth(8), Some("8  \u{265c}  \u{265e}  \u{265d}  \u{265a}  \u{265b}  \u{265d}  \u{265e}  \u{265c}  8"));
+        assert_eq!(diagram.lines().count(), 10);
+
+        assert!(board.display().colors(true).to_string().contains("\x1b[0m"));
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use types::moves::Move;
+
+use crate::board::Board;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum EpdError {
+    #[error("EPD is missing position fields.")]
+    SpacingError,
+    #[error("Invalid position: {0}")]
+    FenError(String),
+    #[error("Unterminated string operand.")]
+    StringError,
+    #[error("Opcode {0} has an invalid operand: {1}")]
+    OperandError(String, String),
+    #[error("Opcode {0} contains an illegal move: {1}")]
+    MoveError(String, String),
+}
+
+/// Typed operand of an EPD opcode
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operand {
+    /// Moves of `bm`, `am` and `sm`, or the line of `pv` played from the position
+    Moves(Vec<Move>),
+    /// Counters and scores such as `acd`, `ce`, `dm` and the perft counts `D1`..`D6`
+    Integer(i64),
+    /// Text of `id`, `c0`..`c9` and other string opcodes
+    String(String),
+    /// Operands of opcodes without a known type
+    Raw(Vec<String>),
+}
+
+/// An EPD record: a position followed by a set of opcodes
+#[derive(Clone, Debug)]
+pub struct Epd {
+    pub board: Board,
+    pub opcodes: BTreeMap<String, Operand>,
+}
+
+impl Epd {
+    pub fn new(board: Board) -> Self {
+        Epd { board, opcodes: BTreeMap::new() }
+    }
+
+    pub fn get(&self, opcode: &str) -> Option<&Operand> {
+        self.opcodes.get(opcode)
+    }
+
+    pub fn integer(&self, opcode: &str) -> Option<i64> {
+        match self.opcodes.get(opcode) {
+            Some(Operand::Integer(n)) => Some(*n),
+            _ => None
+        }
+    }
+
+    pub fn string(&self, opcode: &str) -> Option<&str> {
+        match self.opcodes.get(opcode) {
+            Some(Operand::String(s)) => Some(s),
+            _ => None
+        }
+    }
+
+    pub fn moves(&self, opcode: &str) -> Option<&[Move]> {
+        match self.opcodes.get(opcode) {
+            Some(Operand::Moves(moves)) => Some(moves),
+            _ => None
+        }
+    }
+
+    /// Returns the perft node count stored under `D{depth}`
+    pub fn perft(&self, depth: usize) -> Option<u64> {
+        self.integer(&format!("D{depth}")).map(|n| n as u64)
+    }
+}
+
+fn is_string_opcode(opcode: &str) -> bool {
+    matches!(opcode, "id" | "eco" | "nic" | "tcgs" | "tcri" | "tcsi")
+        || (opcode.len() == 2 && (opcode.starts_with('c') || opcode.starts_with('v')) && opcode.as_bytes()[1].is_ascii_digit())
+}
+
+fn is_integer_opcode(opcode: &str) -> bool {
+    matches!(opcode, "acd" | "acn" | "acs" | "ce" | "dm" | "fmvn" | "hmvc" | "rc")
+        || (opcode.len() >= 2 && opcode.starts_with('D') && opcode[1..].bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Splits the opcode section into opcodes and their operands, honouring quotes
+fn split_operations(s: &str) -> Result<Vec<Vec<String>>, EpdError> {
+    let mut operations = vec![];
+    let mut tokens = vec![];
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => {
+                if !tokens.is_empty() {
+                    operations.push(std::mem::take(&mut tokens));
+                }
+            }
+            '"' => {
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => string.extend(chars.next()),
+                        Some(c) => string.push(c),
+                        None => return Err(EpdError::StringError)
+                    }
+                }
+                tokens.push(string);
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut token = String::from(c);
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == ';' || c == '"' {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+                tokens.push(token);
+            }
+        }
+    }
+    if !tokens.is_empty() {
+        operations.push(tokens);
+    }
+    Ok(operations)
+}
+
+impl FromStr for Epd {
+    type Err = EpdError;
+    /// Parses an EPD record; a full six-field FEN followed by opcodes is accepted too
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut fields = vec![];
+        let mut rest = s;
+        for _ in 0..4 {
+            rest = rest.trim_start();
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            if end == 0 {
+                return Err(EpdError::SpacingError);
+            }
+            fields.push(&rest[..end]);
+            rest = &rest[end..];
+        }
+
+        // Clock fields of a full FEN
+        let mut clocks = (String::from("0"), String::from("1"));
+        if let Some((half, after)) = rest.trim_start().split_once(char::is_whitespace) {
+            let (full, after) = after.trim_start().split_once(char::is_whitespace).unwrap_or((after.trim_start(), ""));
+            if half.parse::<u8>().is_ok() && full.parse::<u16>().is_ok() {
+                clocks = (half.to_string(), full.to_string());
+                rest = after;
+            }
+        }
+
+        let fen = format!("{} {} {}", fields.join(" "), clocks.0, clocks.1);
+        let mut board = Board::from_str(&fen).map_err(|_| EpdError::FenError(fields.join(" ")))?;
+        let operations = split_operations(rest)?;
+
+        // Clock opcodes have to be applied before any move is resolved
+        for operation in &operations {
+            let error = || EpdError::OperandError(operation[0].clone(), operation[1..].join(" "));
+            let value = || operation.get(1).map(String::as_str).unwrap_or_default();
+            match operation[0].as_str() {
+                "hmvc" => board.halfmoves = value().parse().map_err(|_| error())?,
+                "fmvn" => board.fullmoves = value().parse().map_err(|_| error())?,
+                _ => {}
+            }
+        }
+
+        let mut epd = Epd::new(board);
+        for operation in operations {
+            let (opcode, operands) = (operation[0].clone(), &operation[1..]);
+            let operand = match opcode.as_str() {
+                "bm" | "am" | "sm" => {
+                    let moves = operands.iter()
+                        .map(|san| board.parse_san(san).map_err(|_| EpdError::MoveError(opcode.clone(), san.clone())))
+                        .collect::<Result<_, _>>()?;
+                    Operand::Moves(moves)
+                }
+                "pv" => {
+                    let mut line = board;
+                    let mut moves = vec![];
+                    for san in operands {
+                        let mv = line.parse_san(san).map_err(|_| EpdError::MoveError(opcode.clone(), san.clone()))?;
+                        line.apply_move(&mv);
+                        moves.push(mv);
+                    }
+                    Operand::Moves(moves)
+                }
+                op if is_integer_opcode(op) => {
+                    let n = match operands {
+                        [n] => n.parse().ok(),
+                        _ => None
+                    };
+                    Operand::Integer(n.ok_or(EpdError::OperandError(opcode.clone(), operands.join(" ")))?)
+                }
+                op if is_string_opcode(op) => Operand::String(operands.join(" ")),
+                _ => Operand::Raw(operands.to_vec())
+            };
+            epd.opcodes.insert(opcode, operand);
+        }
+        Ok(epd)
+    }
+}
+
+/// Wraps `s` in double quotes, escaping backslashes and quotes
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Writes the four position fields followed by every opcode, moves in SAN
+impl std::fmt::Display for Epd {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fen = self.board.fen();
+        let fields: Vec<&str> = fen.split_whitespace().take(4).collect();
+        write!(f, "{}", fields.join(" "))?;
+        for (opcode, operand) in &self.opcodes {
+            write!(f, " {opcode}")?;
+            match operand {
+                Operand::Moves(moves) if opcode == "pv" => {
+                    let mut board = self.board;
+                    for mv in moves {
+                        write!(f, " {}", board.san(mv))?;
+                        board.apply_move(mv);
+                    }
+                }
+                Operand::Moves(moves) => {
+                    for mv in moves {
+                        write!(f, " {}", self.board.san(mv))?;
+                    }
+                }
+                Operand::Integer(n) => write!(f, " {n}")?,
+                Operand::String(s) => write!(f, " {}", quote(s))?,
+                Operand::Raw(operands) => {
+                    for operand in operands {
+                        // Tokens that were quoted strings keep their quotes
+                        if operand.is_empty() || operand.contains(|c: char| c.is_whitespace() || c == ';' || c == '"') {
+                            write!(f, " {}", quote(operand))?;
+                        } else {
+                            write!(f, " {operand}")?;
+                        }
+                    }
+                }
+            }
+            write!(f, ";")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::epd::{Epd, EpdError, Operand};
+
+    #[test]
+    fn test_epd() {
+        let record = "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - \
+                      acd 12; bm Bb5 Bc4; ce 35; c0 \"Ruy; or Italian\"; id \"open.001\"; pv Bb5 a6 Ba4;";
+        let epd = Epd::from_str(record).unwrap();
+        assert_eq!(epd.integer("acd"), Some(12));
+        assert_eq!(epd.integer("ce"), Some(35));
+        assert_eq!(epd.string("id"), Some("open.001"));
+        assert_eq!(epd.string("c0"), Some("Ruy; or Italian"));
+        let bm: Vec<String> = epd.moves("bm").unwrap().iter().map(|mv| mv.to_string()).collect();
+        assert_eq!(bm, vec!["f1b5", "f1c4"]);
+        assert_eq!(epd.moves("pv").unwrap().len(), 3);
+        assert_eq!(epd.to_string(), "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - \
+                                     acd 12; bm Bb5 Bc4; c0 \"Ruy; or Italian\"; ce 35; id \"open.001\"; pv Bb5 a6 Ba4;");
+
+        let perft = Epd::from_str("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 ;D1 20 ;D2 400 ;D3 8902").unwrap();
+        assert_eq!(perft.perft(3), Some(8902));
+        assert_eq!(perft.board.fullmoves, 1);
+
+        let epd = Epd::from_str("4k3/8/8/8/8/8/8/4K3 b - - hmvc 7; fmvn 40; noop; xyz a b;").unwrap();
+        assert_eq!((epd.board.halfmoves, epd.board.fullmoves), (7, 40));
+        assert_eq!(epd.get("xyz"), Some(&Operand::Raw(vec![String::from("a"), String::from("b")])));
+
+        // A two-digit halfmove clock must not be mistaken for the fullmove number
+        let epd = Epd::from_str("4k3/8/8/8/8/8/8/4K3 w - - 10 1 id \"x\";").unwrap();
+        assert_eq!((epd.board.halfmoves, epd.board.fullmoves), (10, 1));
+        assert_eq!(epd.to_string(), "4k3/8/8/8/8/8/8/4K3 w - - id \"x\";");
+
+        // Quoted operands of unknown opcodes keep their quotes
+        let record = "4k3/8/8/8/8/8/8/4K3 w - - xyz a \"b c\";";
+        let epd = Epd::from_str(record).unwrap();
+        assert_eq!(epd.get("xyz"), Some(&Operand::Raw(vec![String::from("a"), String::from("b c")])));
+        assert_eq!(epd.to_string(), record);
+
+        assert_eq!(Epd::from_str("4k3/8/8/8/8/8/8/4K3 w - - bm Ke2 Kd9;").unwrap_err(), EpdError::MoveError(String::from("bm"), String::from("Kd9")));
+        assert_eq!(Epd::from_str("4k3/8/8/8/8/8/8/4K3 w").unwrap_err(), EpdError::SpacingError);
+    }
+}
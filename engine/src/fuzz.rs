@@ -0,0 +1,201 @@
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use types::moves::Move;
+use types::piece::Piece;
+
+use crate::board::Board;
+use crate::perft::{parse_suite, STANDARD_SUITE};
+use crate::random::Rng;
+use crate::reference::Mailbox;
+
+/// How a generator disagrees with the reference in a position
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Discrepancy {
+    /// The move is legal but not generated
+    Missing(Move),
+    /// The move is generated but not legal
+    Extra(Move),
+    /// `Board` rejects a FEN the reference accepts
+    Rejected,
+}
+
+/// Smallest position found where a generator disagrees with the reference
+#[derive(Clone, Debug, PartialEq)]
+pub struct FuzzFailure {
+    pub fen: String,
+    pub discrepancy: Discrepancy,
+}
+
+impl std::fmt::Display for FuzzFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.discrepancy {
+            Discrepancy::Missing(mv) => write!(f, "{}: {} is legal but not generated", self.fen, mv),
+            Discrepancy::Extra(mv) => write!(f, "{}: {} is generated but not legal", self.fen, mv),
+            Discrepancy::Rejected => write!(f, "{}: rejected by Board but accepted by the reference", self.fen)
+        }
+    }
+}
+
+/// Returns the first move, in UCI order, on which `generate` and the reference disagree,
+/// or `Rejected` if only the reference can parse `fen`
+pub fn compare_with<G: Fn(&Board) -> Vec<Move>>(fen: &str, generate: &G) -> Option<Discrepancy> {
+    let mailbox = Mailbox::from_fen(fen)?;
+    let Ok(board) = Board::from_str(fen) else {
+        return Some(Discrepancy::Rejected);
+    };
+    let mut generated = generate(&board);
+    let mut legal = mailbox.legal_moves();
+    generated.sort_by_key(|mv| (mv.to_string(), mv.bits()));
+    legal.sort_by_key(|mv| (mv.to_string(), mv.bits()));
+    let missing = legal.iter().find(|mv| !generated.contains(mv));
+    let extra = generated.iter().find(|mv| !legal.contains(mv));
+    match (missing, extra) {
+        (Some(a), Some(b)) if a.to_string() <= b.to_string() => Some(Discrepancy::Missing(*a)),
+        (_, Some(b)) => Some(Discrepancy::Extra(*b)),
+        (a, None) => a.map(|a| Discrepancy::Missing(*a))
+    }
+}
+
+/// Removes pieces, castling rights and the en-passant square from a failing position
+/// for as long as the position stays legal and the generator keeps failing
+pub fn shrink_with<G: Fn(&Board) -> Vec<Move>>(fen: &str, generate: &G) -> Option<FuzzFailure> {
+    let mut discrepancy = compare_with(fen, generate)?;
+    let mut current = Mailbox::from_fen(fen)?;
+    // Kept verbatim until something is removed, since the clocks may be what `Board` rejects
+    let mut current_fen = fen.to_string();
+    loop {
+        let mut candidates = vec![];
+        for sq in 0..64 {
+            if current.squares[sq].is_some_and(|(_, piece)| piece != Piece::King) {
+                let mut candidate = current;
+                candidate.squares[sq] = None;
+                candidates.push(candidate);
+            }
+        }
+        for right in 0..4 {
+            if current.castling[right] {
+                let mut candidate = current;
+                candidate.castling[right] = false;
+                candidates.push(candidate);
+            }
+        }
+        if current.enpassant.is_some() {
+            candidates.push(Mailbox { enpassant: None, ..current });
+        }
+
+        let smaller = candidates.into_iter().find_map(|candidate| {
+            // Reparsing drops rights and en-passant squares the removals invalidated
+            let candidate = Mailbox::from_fen(&candidate.fen())?;
+            let mut waiting = candidate;
+            waiting.turn = !candidate.turn;
+            if waiting.in_check() {
+                return None;
+            }
+            compare_with(&candidate.fen(), generate).map(|failure| (candidate, failure))
+        });
+        match smaller {
+            Some((candidate, failure)) => {
+                current = candidate;
+                current_fen = candidate.fen();
+                discrepancy = failure;
+            }
+            None => return Some(FuzzFailure { fen: current_fen, discrepancy })
+        }
+    }
+}
+
+/// Plays `games` random games of up to `plies` moves, starting from the reference positions
+/// of the perft suite in turn, and compares `generate` with the reference at every ply.
+/// Returns the number of positions compared.
+pub fn fuzz_with<G: Fn(&Board) -> Vec<Move>>(seed: u64, games: usize, plies: usize, generate: &G) -> Result<u64, FuzzFailure> {
+    let starts: Vec<Board> = parse_suite(STANDARD_SUITE).expect("Invalid perft suite").into_iter().map(|(_, epd)| epd.board).collect();
+    let mut rng = Rng::new(seed);
+    let mut positions = 0;
+    for game in 0..games {
+        let mut board = starts[game % starts.len()];
+        for _ in 0..plies {
+            let fen = board.fen();
+            positions += 1;
+            if compare_with(&fen, generate).is_some() {
+                return Err(shrink_with(&fen, generate).expect("Failure vanished while shrinking"));
+            }
+            let moves = generate(&board);
+            let Some(mv) = rng.choose(&moves) else { break };
+            board.apply_move(mv);
+        }
+    }
+    Ok(positions)
+}
+
+/// Compares `Board::moves` with the reference on random games
+pub fn fuzz(seed: u64, games: usize, plies: usize) -> Result<u64, FuzzFailure> {
+    fuzz_with(seed, games, plies, &|board: &Board| board.moves().to_vec())
+}
+
+/// Keeps fuzzing `Board::moves` with consecutive seeds until `duration` has passed
+pub fn fuzz_for(seed: u64, duration: Duration) -> Result<u64, FuzzFailure> {
+    let start = Instant::now();
+    let mut positions = 0;
+    let mut seed = seed;
+    while start.elapsed() < duration {
+        positions += fuzz(seed, 100, 300)?;
+        seed += 1;
+    }
+    Ok(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use types::moves::Move;
+
+    use crate::board::Board;
+    use crate::fuzz::{compare_with, fuzz, fuzz_for, fuzz_with, shrink_with, Discrepancy, FuzzFailure};
+
+    #[test]
+    fn test_fuzz() {
+        if let Err(failure) = fuzz(0, 30, 200) {
+            panic!("{failure}");
+        }
+    }
+
+    #[test]
+    fn test_fuzz_shrinks_failures() {
+        // A generator that forgets knight underpromotions
+        let buggy = |board: &Board| -> Vec<Move> {
+            board.moves().iter().filter(|mv| !mv.to_string().ends_with('n')).copied().collect()
+        };
+        let failure = shrink_with("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b kq - 0 1", &buggy).unwrap();
+        assert_eq!(failure.to_string(), "4k3/8/8/8/8/8/1p6/6K1 b - - 0 1: b2b1n is legal but not generated");
+        assert!(matches!(failure.discrepancy, Discrepancy::Missing(_)));
+
+        let failure: FuzzFailure = fuzz_with(0, 30, 200, &buggy).unwrap_err();
+        let placement = failure.fen.split(' ').next().unwrap();
+        assert_eq!(placement.matches(|c: char| c.is_ascii_alphabetic()).count(), 3);
+    }
+
+    #[test]
+    fn test_fuzz_reports_rejected_fens() {
+        let generate = |board: &Board| board.moves().to_vec();
+        // The reference ignores the clocks, `Board` does not fit 300 into the halfmove clock
+        let fen = "4k3/8/8/8/8/8/8/4K3 w - - 300 1";
+        assert_eq!(compare_with(fen, &generate), Some(Discrepancy::Rejected));
+        let failure = shrink_with(fen, &generate).unwrap();
+        assert_eq!(failure.to_string(), "4k3/8/8/8/8/8/8/4K3 w - - 300 1: rejected by Board but accepted by the reference");
+
+        // Nothing to compare when neither side can parse the FEN
+        assert_eq!(compare_with("4k3/8/8/8/8/8/8/4K3 x - - 0 1", &generate), None);
+    }
+
+    #[test]
+    #[ignore = "fuzzes for FUZZ_SECONDS seconds (an hour by default) from FUZZ_SEED, use --release"]
+    fn test_fuzz_soak() {
+        let seconds = std::env::var("FUZZ_SECONDS").ok().and_then(|s| s.parse().ok()).unwrap_or(3600);
+        let seed = std::env::var("FUZZ_SEED").ok().and_then(|s| s.parse().ok()).unwrap_or(0);
+        if let Err(failure) = fuzz_for(seed, Duration::from_secs(seconds)) {
+            panic!("{failure}");
+        }
+    }
+}
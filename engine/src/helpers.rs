@@ -3,7 +3,7 @@ use types::{square::Square, bitboard::BitBoard};
 pub fn square_idx(sq: &str) -> usize {
     let col = sq.chars().next().expect("Invalid square");
     let row = sq.chars().nth(1).expect("Invalid square");
-    8 * row as usize - 49 + col as usize
+    8 * (row as usize - 49) + col as usize - 97
 }
 
 pub const fn piece_idx(piece: char) -> usize {
@@ -18,7 +18,7 @@ pub const fn piece_idx(piece: char) -> usize {
     }
 }
 
-pub const fn squares_between(from: Square, to: Square) -> BitBoard {
+pub fn squares_between(from: Square, to: Square) -> BitBoard {
     const fn squares_between(from: Square, to: Square) -> BitBoard {
         let df = to.file() as i8 - from.file() as i8;
         let dr = to.rank() as i8 - from.rank() as i8;
@@ -37,7 +37,7 @@ pub const fn squares_between(from: Square, to: Square) -> BitBoard {
         }
         between
     }
-    const TABLE: [[BitBoard; Square::NUM]; Square::NUM] = {
+    static TABLE: [[BitBoard; Square::NUM]; Square::NUM] = {
         let mut table = [[BitBoard::EMPTY; Square::NUM]; Square::NUM];
         let mut i = 0;
         while i < Square::NUM {
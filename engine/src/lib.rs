@@ -1,3 +1,16 @@
+#[cfg(feature = "attack-maps")]
+pub mod attacks;
 pub mod board;
+pub mod display;
+pub mod epd;
+pub mod fuzz;
+pub mod notation;
+pub mod perft;
+pub mod pgn;
+pub mod random;
+pub mod reference;
+pub mod sliders;
+pub mod svg;
+pub mod zobrist;
 mod moves;
-mod helpers;
\ No newline at end of file
+mod helpers;
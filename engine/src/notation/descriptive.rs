@@ -0,0 +1,277 @@
+use thiserror::Error;
+
+use types::color::Color;
+use types::moves::{Move, MoveKind};
+use types::piece::{Piece, PieceLetters, PromotionPiece};
+use types::square::Square;
+
+use crate::board::{Board, PIECES};
+
+/// Descriptive file names from the a-file to the h-file
+const FILES: [&str; 8] = ["QR", "QN", "QB", "Q", "K", "KB", "KN", "KR"];
+
+#[derive(Debug, Error, PartialEq)]
+pub enum DescriptiveError {
+    #[error("{0} is not valid descriptive notation.")]
+    SyntaxError(String),
+    #[error("{0} is not a legal move in this position.")]
+    IllegalMoveError(String),
+    #[error("{0} matches more than one legal move.")]
+    AmbiguousMoveError(String),
+}
+
+/// Set of squares described by a square name such as `KB3` or `B3`
+struct SquareSpec {
+    files: u8,
+    rank: usize,
+}
+
+impl SquareSpec {
+    fn contains(&self, sq: Square) -> bool {
+        self.files & (1 << sq.file()) != 0 && sq.rank() == self.rank
+    }
+}
+
+/// Piece described by a name such as `P`, `KBP`, `QN` or `N(KB3)`
+struct PieceSpec {
+    piece: Piece,
+    files: u8,
+    square: Option<SquareSpec>,
+}
+
+impl PieceSpec {
+    fn matches(&self, piece: Option<Piece>, sq: Square) -> bool {
+        piece == Some(self.piece)
+            && self.files & (1 << sq.file()) != 0
+            && self.square.as_ref().is_none_or(|spec| spec.contains(sq))
+    }
+}
+
+enum Target {
+    Square(SquareSpec),
+    Piece(PieceSpec),
+}
+
+/// Returns the square of the piece captured by `mv`
+const fn captured_square(mv: &Move) -> Square {
+    match mv.kind() {
+        MoveKind::EnPassant => Square(mv.to().0 ^ 8),
+        _ => mv.to()
+    }
+}
+
+impl Board {
+    /// Converts an absolute rank to the rank counted from the side to move
+    const fn relative_rank(&self, rank: usize) -> usize {
+        match self.turn {
+            Color::White => rank,
+            Color::Black => 7 - rank
+        }
+    }
+
+    fn parse_square_spec(&self, s: &str) -> Option<SquareSpec> {
+        let rank = s.chars().last()?.to_digit(10).filter(|rank| (1..=8).contains(rank))? as usize;
+        let files = match &s[..s.len() - 1] {
+            "R" => 0x81,
+            "N" => 0x42,
+            "B" => 0x24,
+            name => 1 << FILES.iter().position(|file| *file == name)?
+        };
+        Some(SquareSpec { files, rank: self.relative_rank(rank - 1) })
+    }
+
+    fn parse_piece_spec(&self, s: &str) -> Option<PieceSpec> {
+        let (name, square) = match s.split_once(['(', '/']) {
+            Some((name, square)) => (name, Some(self.parse_square_spec(square.trim_end_matches(')'))?)),
+            None => (s, None)
+        };
+        let piece = PIECES.into_iter().find(|&piece| name.ends_with(PieceLetters::ENGLISH.get(piece)))?;
+        let files = match (piece, &name[..name.len() - PieceLetters::ENGLISH.get(piece).len()]) {
+            (_, "") => 0xff,
+            // Pawns on either wing, resolved against the legal moves like `N-B3`
+            (Piece::Pawn, "R") => 0x81,
+            (Piece::Pawn, "N") => 0x42,
+            (Piece::Pawn, "B") => 0x24,
+            (Piece::Pawn, file) => 1 << FILES.iter().position(|name| *name == file)?,
+            (_, "K") => 0xf0,
+            (_, "Q") => 0x0f,
+            _ => return None
+        };
+        Some(PieceSpec { piece, files, square })
+    }
+
+    /// Parses a move in English descriptive notation, e.g. `P-K4`, `NxQP` or `P-QB8=Q`
+    ///
+    /// Squares are named from the point of view of the side to move and
+    /// ambiguous names like `N-B3` are resolved against the legal moves.
+    pub fn parse_descriptive(&self, s: &str) -> Result<Move, DescriptiveError> {
+        let syntax_error = || DescriptiveError::SyntaxError(s.to_string());
+        // Older sources write knights as Kt
+        let upper: String = s.to_uppercase().replace("KT", "N").chars().filter(|c| !c.is_whitespace()).collect();
+        let mut str = upper.trim_end_matches(['!', '?', '+', '#']);
+        for suffix in ["E.P.", "EP", "CH", "MATE", "DIS"] {
+            str = str.strip_suffix(suffix).unwrap_or(str).trim_end_matches(['.', ',']);
+        }
+
+        let castle = match str.replace('0', "O").as_str() {
+            "O-O" => Some(MoveKind::KingCastle),
+            "O-O-O" => Some(MoveKind::QueenCastle),
+            _ => None
+        };
+        if let Some(kind) = castle {
+            return self.moves()
+                .into_iter()
+                .find(|mv| mv.kind() == kind)
+                .ok_or(DescriptiveError::IllegalMoveError(s.to_string()));
+        }
+
+        let mut promotion = None;
+        let chars: Vec<char> = str.chars().collect();
+        if chars.len() > 2 {
+            let (marker, letter) = match chars[chars.len() - 1] {
+                ')' if chars.len() > 3 => (chars[chars.len() - 3], chars[chars.len() - 2]),
+                letter => (chars[chars.len() - 2], letter)
+            };
+            if matches!(marker, '=' | '/' | '(') {
+                promotion = Some(match letter {
+                    'Q' => PromotionPiece::Queen,
+                    'R' => PromotionPiece::Rook,
+                    'B' => PromotionPiece::Bishop,
+                    'N' => PromotionPiece::Knight,
+                    _ => return Err(syntax_error())
+                });
+                str = &str[..str.rfind(marker).unwrap_or(str.len())];
+            }
+        }
+
+        let idx = str.find(['-', 'X']).ok_or_else(syntax_error)?;
+        let mover = self.parse_piece_spec(&str[..idx]).ok_or_else(syntax_error)?;
+        let target = match &str[idx..idx + 1] {
+            "-" => Target::Square(self.parse_square_spec(&str[idx + 1..]).ok_or_else(syntax_error)?),
+            _ => Target::Piece(self.parse_piece_spec(&str[idx + 1..]).ok_or_else(syntax_error)?)
+        };
+
+        let mut candidates = self.moves().into_iter().filter(|mv| {
+            mover.matches(self.moved_piece(mv), mv.from())
+                && (promotion.is_none() || mv.promotion() == promotion)
+                && match &target {
+                    Target::Square(spec) => spec.contains(mv.to()),
+                    Target::Piece(spec) => mv.is_capture() && spec.matches(self.captured_piece(mv), captured_square(mv))
+                }
+        });
+        match (candidates.next(), candidates.next()) {
+            (Some(mv), None) => Ok(mv),
+            (None, _) => Err(DescriptiveError::IllegalMoveError(s.to_string())),
+            (Some(_), Some(_)) => Err(DescriptiveError::AmbiguousMoveError(s.to_string()))
+        }
+    }
+
+    /// Returns the descriptive names of `sq`, shortest first
+    fn square_names(&self, sq: Square) -> Vec<String> {
+        let rank = self.relative_rank(sq.rank()) + 1;
+        let file = FILES[sq.file()];
+        let mut names = vec![];
+        if file.len() == 2 {
+            names.push(format!("{}{rank}", &file[1..]));
+        }
+        names.push(format!("{file}{rank}"));
+        names
+    }
+
+    /// Returns the descriptive names of `piece` on `sq`, shortest first
+    fn piece_names(&self, piece: Piece, sq: Square) -> Vec<String> {
+        let file = FILES[sq.file()];
+        let full = self.square_names(sq).pop().unwrap_or_default();
+        let mut names = vec![piece.to_string()];
+        match piece {
+            Piece::Pawn if file.len() == 2 => names.extend([format!("{}{piece}", &file[1..]), format!("{file}{piece}")]),
+            Piece::Pawn => names.push(format!("{file}{piece}")),
+            _ => names.push(format!("{}{piece}", &file[..1]))
+        }
+        names.push(format!("{piece}({full})"));
+        names
+    }
+
+    /// Formats `mv` in English descriptive notation, e.g. `P-K4`, `NxP` or `R(QR1)-Q1`
+    ///
+    /// The shortest description that identifies the move among the legal moves is used.
+    /// `mv` must be legal in the current position.
+    pub fn descriptive(&self, mv: &Move) -> String {
+        let suffix = match self.check_suffix(mv) {
+            "+" => "ch",
+            "#" => "mate",
+            _ => ""
+        };
+        match mv.kind() {
+            MoveKind::KingCastle => return format!("O-O{suffix}"),
+            MoveKind::QueenCastle => return format!("O-O-O{suffix}"),
+            _ => {}
+        }
+
+        let movers = self.piece_names(self.moved_piece(mv).expect("no piece to move"), mv.from());
+        let targets: Vec<String> = match self.captured_piece(mv) {
+            Some(piece) => {
+                self.piece_names(piece, captured_square(mv)).into_iter().map(|name| format!("x{name}")).collect()
+            }
+            None => self.square_names(mv.to()).into_iter().map(|name| format!("-{name}")).collect()
+        };
+        let promotion = match mv.promotion() {
+            Some(promotion) => format!("={promotion}"),
+            None => String::new()
+        };
+
+        let mut candidates: Vec<String> = movers.iter()
+            .flat_map(|mover| targets.iter().map(|target| format!("{mover}{target}{promotion}")).collect::<Vec<_>>())
+            .collect();
+        candidates.sort_by_key(|str| str.len());
+        let description = candidates.iter()
+            .find(|str| self.parse_descriptive(str).as_ref() == Ok(mv))
+            .or(candidates.last())
+            .cloned()
+            .unwrap_or_default();
+        format!("{description}{suffix}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::board::Board;
+    use crate::notation::descriptive::DescriptiveError;
+
+    #[test]
+    fn test_descriptive() {
+        let mut board = Board::default();
+        for (uci, descriptive) in [("e2e4", "P-K4"), ("d7d5", "P-Q4"), ("e4d5", "PxP"), ("g8f6", "N-KB3"), ("b1c3", "N-QB3")] {
+            let mv = board.parse_uci(uci).unwrap();
+            assert_eq!(board.descriptive(&mv), descriptive);
+            assert_eq!(board.parse_descriptive(descriptive), Ok(mv));
+            board.apply_move(&mv);
+        }
+        assert_eq!(board.parse_descriptive("N-B3").unwrap().to_string(), "b8c6");
+        assert_eq!(board.parse_descriptive("QxP").unwrap().to_string(), "d8d5");
+        assert_eq!(board.parse_descriptive("QKt-Q2").unwrap().to_string(), "b8d7");
+        assert_eq!(board.parse_descriptive("N-Q2"), Err(DescriptiveError::AmbiguousMoveError(String::from("N-Q2"))));
+        assert_eq!(board.parse_descriptive("N-K4"), Err(DescriptiveError::IllegalMoveError(String::from("N-K4"))));
+
+        let board = Board::from_str("1r2k3/2P5/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+        assert_eq!(board.parse_descriptive("P-QB8=Q").unwrap().to_string(), "c7c8q");
+        assert_eq!(board.parse_descriptive("PxR(N)").unwrap().to_string(), "c7b8n");
+        assert_eq!(board.parse_descriptive("0-0-0").unwrap().to_string(), "e1c1");
+        assert_eq!(board.descriptive(&board.parse_uci("c7c8q").unwrap()), "P-B8=Qch");
+        assert_eq!(board.descriptive(&board.parse_uci("a1a7").unwrap()), "R-QR7");
+        assert_eq!(board.parse_descriptive("R-B1"), Err(DescriptiveError::AmbiguousMoveError(String::from("R-B1"))));
+        assert_eq!(board.parse_descriptive("R-QB1").unwrap().to_string(), "a1c1");
+        assert_eq!(board.descriptive(&board.parse_uci("h1f1").unwrap()), "R-KB1");
+
+        // Pawns may be named without their wing when that leaves a single one
+        let board = Board::from_str("4k3/8/8/3p1p2/4P3/8/1P5P/4K3 w - - 0 1").unwrap();
+        assert_eq!(board.parse_descriptive("PxBP").unwrap().to_string(), "e4f5");
+        assert_eq!(board.descriptive(&board.parse_uci("e4f5").unwrap()), "PxBP");
+        assert_eq!(board.parse_descriptive("NP-N4").unwrap().to_string(), "b2b4");
+        assert_eq!(board.parse_descriptive("RP-R3").unwrap().to_string(), "h2h3");
+        assert_eq!(board.parse_descriptive("PxP"), Err(DescriptiveError::AmbiguousMoveError(String::from("PxP"))));
+        assert_eq!(Board::default().parse_descriptive("BP-B3"), Err(DescriptiveError::AmbiguousMoveError(String::from("BP-B3"))));
+    }
+}
@@ -0,0 +1,180 @@
+use types::moves::{Move, MoveKind};
+use types::piece::{Piece, PieceLetters, PromotionPiece};
+
+use crate::board::{Board, PIECES};
+use crate::notation::san::SanError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlgebraicStyle {
+    /// Only the destination plus whatever disambiguation is needed, e.g. `Nf3`
+    Short,
+    /// Origin and destination separated by `-` or `x`, e.g. `Ng1-f3`
+    Long,
+}
+
+/// Describes how `Board::format_move` writes a move
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MoveFormat {
+    pub letters: PieceLetters,
+    pub style: AlgebraicStyle,
+}
+
+impl MoveFormat {
+    /// Standard Algebraic Notation with English piece letters
+    pub const SAN: MoveFormat = MoveFormat::new(PieceLetters::ENGLISH, AlgebraicStyle::Short);
+    /// Short algebraic notation with figurines instead of piece letters
+    pub const FIGURINE: MoveFormat = MoveFormat::new(PieceLetters::FIGURINE, AlgebraicStyle::Short);
+    /// Long algebraic notation with English piece letters
+    pub const LAN: MoveFormat = MoveFormat::new(PieceLetters::ENGLISH, AlgebraicStyle::Long);
+
+    pub const fn new(letters: PieceLetters, style: AlgebraicStyle) -> Self {
+        MoveFormat { letters, style }
+    }
+}
+
+impl Board {
+    /// Formats `mv` in algebraic notation using the piece letters and style of `format`
+    ///
+    /// `mv` must be legal in the current position.
+    pub fn format_move(&self, mv: &Move, format: &MoveFormat) -> String {
+        let mut str = String::new();
+        let piece = self.moved_piece(mv).expect("no piece to move");
+
+        match mv.kind() {
+            MoveKind::KingCastle => str.push_str("O-O"),
+            MoveKind::QueenCastle => str.push_str("O-O-O"),
+            _ => {
+                if piece != Piece::Pawn {
+                    str.push_str(format.letters.get(piece));
+                }
+                match format.style {
+                    AlgebraicStyle::Short => {
+                        if piece != Piece::Pawn {
+                            str.push_str(&self.disambiguation(mv, piece));
+                        } else if mv.is_capture() {
+                            str.push((b'a' + mv.from().file() as u8) as char);
+                        }
+                        if mv.is_capture() {
+                            str.push('x');
+                        }
+                    }
+                    AlgebraicStyle::Long => {
+                        str.push_str(&mv.from().to_string());
+                        str.push(if mv.is_capture() { 'x' } else { '-' });
+                    }
+                }
+                str.push_str(&mv.to().to_string());
+                if let Some(promotion) = mv.promotion() {
+                    str.push('=');
+                    str.push_str(format.letters.get(Piece::from(promotion)));
+                }
+            }
+        }
+
+        str.push_str(self.check_suffix(mv));
+        str
+    }
+
+    /// Parses a move written in algebraic notation with the piece letters of `format`
+    ///
+    /// The letters are mapped back to English and the move is read with
+    /// `parse_san`, so both styles and the same deviations are accepted.
+    pub fn parse_move(&self, s: &str, format: &MoveFormat) -> Result<Move, SanError> {
+        let mut rest = s.trim();
+        let mut san = String::new();
+
+        // Pawns are never written with a letter, so only a leading piece letter is translated
+        let piece = PIECES.into_iter().find(|&piece| piece != Piece::Pawn && rest.starts_with(format.letters.get(piece)));
+        if let Some(piece) = piece {
+            san.push_str(PieceLetters::ENGLISH.get(piece));
+            rest = &rest[format.letters.get(piece).len()..];
+        }
+
+        // Everything after the destination rank may hold a promotion letter
+        let split = rest.rfind(|c: char| c.is_ascii_digit()).map_or(0, |i| i + 1);
+        san.push_str(&rest[..split]);
+        let mut tail = &rest[split..];
+        'tail: while let Some(c) = tail.chars().next() {
+            for promotion in [PromotionPiece::Queen, PromotionPiece::Rook, PromotionPiece::Bishop, PromotionPiece::Knight] {
+                if let Some(after) = tail.strip_prefix(format.letters.get(Piece::from(promotion))) {
+                    san.push_str(PieceLetters::ENGLISH.get(Piece::from(promotion)));
+                    tail = after;
+                    continue 'tail;
+                }
+            }
+            san.push(c);
+            tail = &tail[c.len_utf8()..];
+        }
+
+        self.parse_san(&san).map_err(|err| match err {
+            SanError::SyntaxError(_) => SanError::SyntaxError(s.to_string()),
+            SanError::IllegalMoveError(_) => SanError::IllegalMoveError(s.to_string()),
+            SanError::AmbiguousMoveError(_) => SanError::AmbiguousMoveError(s.to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use types::piece::PieceLetters;
+
+    use crate::board::Board;
+    use crate::notation::format::{AlgebraicStyle, MoveFormat};
+
+    #[test]
+    fn test_format_move() {
+        let board = Board::from_str("4k3/1P6/8/3p4/4P3/8/8/4K1N1 w - - 0 1").unwrap();
+        let format = |uci, format| board.format_move(&board.parse_uci(uci).unwrap(), &format);
+
+        let german = MoveFormat::new(PieceLetters::GERMAN, AlgebraicStyle::Short);
+        assert_eq!(format("g1f3", german), "Sf3");
+        assert_eq!(format("b7b8q", german), "b8=D+");
+        assert_eq!(format("g1f3", MoveFormat::new(PieceLetters::FRENCH, AlgebraicStyle::Short)), "Cf3");
+        assert_eq!(format("e1d2", MoveFormat::new(PieceLetters::SPANISH, AlgebraicStyle::Short)), "Rd2");
+        assert_eq!(format("g1f3", MoveFormat::FIGURINE), "\u{2658}f3");
+        assert_eq!(format("g1f3", MoveFormat::LAN), "Ng1-f3");
+        assert_eq!(format("e4d5", MoveFormat::LAN), "e4xd5");
+        assert_eq!(format("b7b8n", MoveFormat::new(PieceLetters::DUTCH, AlgebraicStyle::Long)), "b7-b8=P");
+    }
+
+    #[test]
+    fn test_parse_move() {
+        let dutch = MoveFormat::new(PieceLetters::DUTCH, AlgebraicStyle::Short);
+        let german = MoveFormat::new(PieceLetters::GERMAN, AlgebraicStyle::Short);
+        let board = Board::from_str("4k3/1P6/8/3p4/4P3/8/8/4K1N1 w - - 0 1").unwrap();
+        assert_eq!(board.parse_move("Pf3", &dutch), board.parse_san("Nf3"));
+        assert_eq!(board.parse_move("b8=P", &dutch), board.parse_san("b8=N"));
+        assert_eq!(board.parse_move("Sf3", &german), board.parse_san("Nf3"));
+        assert_eq!(board.parse_move("b8=D+", &german), board.parse_san("b8=Q"));
+        assert_eq!(board.parse_move("b8=L", &german), board.parse_san("b8=B"));
+        assert!(board.parse_move("Tf3", &german).is_err());
+    }
+
+    #[test]
+    fn test_localized_round_trip() {
+        let fens = [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1",
+            "4k3/1P6/8/3p4/4P3/8/8/4K1N1 w - - 0 1",
+            "1k6/8/8/2N1N3/8/2N1N3/8/4K3 w - - 0 1",
+        ];
+        let tables = [
+            PieceLetters::ENGLISH, PieceLetters::GERMAN, PieceLetters::FRENCH, PieceLetters::DUTCH, PieceLetters::SPANISH,
+            PieceLetters::ITALIAN, PieceLetters::PORTUGUESE, PieceLetters::SWEDISH, PieceLetters::FIGURINE,
+        ];
+        for fen in fens {
+            let board = Board::from_str(fen).unwrap();
+            for letters in tables {
+                for style in [AlgebraicStyle::Short, AlgebraicStyle::Long] {
+                    let format = MoveFormat::new(letters, style);
+                    for mv in board.moves() {
+                        let text = board.format_move(&mv, &format);
+                        assert_eq!(board.parse_move(&text, &format), Ok(mv), "{fen} {text}");
+                    }
+                }
+            }
+        }
+    }
+}
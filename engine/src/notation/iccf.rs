@@ -0,0 +1,80 @@
+use thiserror::Error;
+
+use types::moves::Move;
+use types::piece::PromotionPiece;
+use types::square::Square;
+
+use crate::board::Board;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum IccfError {
+    #[error("{0} is not valid ICCF numeric notation.")]
+    SyntaxError(String),
+    #[error("{0} is not a legal move in this position.")]
+    IllegalMoveError(String),
+}
+
+impl Board {
+    /// Formats `mv` in ICCF numeric notation, e.g. `5254` or `57581`
+    ///
+    /// Files and ranks are both numbered 1 to 8 and a fifth digit names the
+    /// promotion piece: 1 queen, 2 rook, 3 bishop, 4 knight.
+    pub fn iccf(&self, mv: &Move) -> String {
+        let (from, to) = (mv.from(), mv.to());
+        let mut str = format!("{}{}{}{}", from.file() + 1, from.rank() + 1, to.file() + 1, to.rank() + 1);
+        if let Some(promotion) = mv.promotion() {
+            str.push_str(&(promotion as usize + 1).to_string());
+        }
+        str
+    }
+
+    /// Parses a move in ICCF numeric notation; castling is written as the king's move
+    pub fn parse_iccf(&self, s: &str) -> Result<Move, IccfError> {
+        let digits: Vec<usize> = s.trim()
+            .chars()
+            .map(|c| c.to_digit(10).map(|d| d as usize))
+            .collect::<Option<_>>()
+            .ok_or(IccfError::SyntaxError(s.to_string()))?;
+        if !(4..=5).contains(&digits.len()) || digits[..4].iter().any(|d| !(1..=8).contains(d)) {
+            return Err(IccfError::SyntaxError(s.to_string()));
+        }
+        let from = Square(8 * (digits[1] - 1) + digits[0] - 1);
+        let to = Square(8 * (digits[3] - 1) + digits[2] - 1);
+        let promotion = match digits.get(4) {
+            None => None,
+            Some(1) => Some(PromotionPiece::Queen),
+            Some(2) => Some(PromotionPiece::Rook),
+            Some(3) => Some(PromotionPiece::Bishop),
+            Some(4) => Some(PromotionPiece::Knight),
+            Some(_) => return Err(IccfError::SyntaxError(s.to_string()))
+        };
+        self.moves()
+            .into_iter()
+            .find(|mv| mv.from() == from && mv.to() == to && mv.promotion() == promotion)
+            .ok_or(IccfError::IllegalMoveError(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::board::Board;
+    use crate::notation::iccf::IccfError;
+
+    #[test]
+    fn test_iccf() {
+        let board = Board::default();
+        let mv = board.parse_iccf("5254").unwrap();
+        assert_eq!(mv.to_string(), "e2e4");
+        assert_eq!(board.iccf(&mv), "5254");
+        assert_eq!(board.parse_iccf("5255"), Err(IccfError::IllegalMoveError(String::from("5255"))));
+        assert_eq!(board.parse_iccf("5259"), Err(IccfError::SyntaxError(String::from("5259"))));
+
+        let board = Board::from_str("3k4/4P3/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        let mv = board.parse_iccf("57584").unwrap();
+        assert_eq!(mv.to_string(), "e7e8n");
+        assert_eq!(board.iccf(&mv), "57584");
+        assert_eq!(board.parse_iccf("5171").unwrap().to_string(), "e1g1");
+    }
+}
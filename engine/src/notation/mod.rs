@@ -0,0 +1,5 @@
+pub mod uci;
+pub mod san;
+pub mod format;
+pub mod descriptive;
+pub mod iccf;
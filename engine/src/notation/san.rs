@@ -0,0 +1,190 @@
+use thiserror::Error;
+
+use types::moves::{Move, MoveKind};
+use types::piece::{Piece, PromotionPiece};
+use types::square::Square;
+
+use crate::board::Board;
+use crate::notation::format::MoveFormat;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum SanError {
+    #[error("{0} is not valid algebraic notation.")]
+    SyntaxError(String),
+    #[error("{0} is not a legal move in this position.")]
+    IllegalMoveError(String),
+    #[error("{0} matches more than one legal move.")]
+    AmbiguousMoveError(String),
+}
+
+impl Board {
+    /// Formats `mv` in Standard Algebraic Notation, e.g. `Nbd7`, `exd5`, `e8=Q+` or `O-O-O#`
+    ///
+    /// `mv` must be legal in the current position.
+    pub fn san(&self, mv: &Move) -> String {
+        self.format_move(mv, &MoveFormat::SAN)
+    }
+
+    /// Parses a move in Standard Algebraic Notation
+    ///
+    /// Common real-world deviations are accepted: `0-0` castling, missing or
+    /// superfluous disambiguation, `exd8Q`/`e8(Q)` promotions, omitted or extra
+    /// capture and check markers and trailing `!?` annotations.
+    pub fn parse_san(&self, s: &str) -> Result<Move, SanError> {
+        let san = s.trim().trim_end_matches(['!', '?', '+', '#']);
+        let san = san.strip_suffix("e.p.").unwrap_or(san).trim_end();
+        let syntax_error = || SanError::SyntaxError(s.to_string());
+
+        let castle = match san.replace('0', "O").as_str() {
+            "O-O" => Some(MoveKind::KingCastle),
+            "O-O-O" => Some(MoveKind::QueenCastle),
+            _ => None
+        };
+        if let Some(kind) = castle {
+            return self.moves()
+                .into_iter()
+                .find(|mv| mv.kind() == kind)
+                .ok_or(SanError::IllegalMoveError(s.to_string()));
+        }
+
+        let mut chars: Vec<char> = san.chars().filter(|c| !matches!(c, 'x' | ':' | '-' | '=' | '(' | ')' | '/')).collect();
+
+        let piece = match chars.first() {
+            Some('K') => Piece::King,
+            Some('Q') => Piece::Queen,
+            Some('R') => Piece::Rook,
+            Some('B') => Piece::Bishop,
+            Some('N') => Piece::Knight,
+            _ => Piece::Pawn
+        };
+        if piece != Piece::Pawn {
+            chars.remove(0);
+        }
+
+        // The destination always ends in a rank, so a trailing letter is a promotion
+        let promotion = match chars.last().map(char::to_ascii_uppercase) {
+            Some('Q') => Some(PromotionPiece::Queen),
+            Some('R') => Some(PromotionPiece::Rook),
+            Some('B') => Some(PromotionPiece::Bishop),
+            Some('N') => Some(PromotionPiece::Knight),
+            _ => None
+        };
+        if promotion.is_some() {
+            chars.pop();
+        }
+
+        let rank = chars.pop().filter(|c| ('1'..='8').contains(c)).ok_or_else(syntax_error)?;
+        let file = chars.pop().filter(|c| ('a'..='h').contains(c)).ok_or_else(syntax_error)?;
+        let to = Square(8 * (rank as usize - '1' as usize) + file as usize - 'a' as usize);
+
+        let (mut from_file, mut from_rank) = (None, None);
+        for c in chars {
+            match c {
+                'a'..='h' if from_file.is_none() => from_file = Some(c as usize - 'a' as usize),
+                '1'..='8' if from_rank.is_none() => from_rank = Some(c as usize - '1' as usize),
+                _ => return Err(syntax_error())
+            }
+        }
+
+        let mut candidates = self.moves().into_iter().filter(|mv| {
+            mv.to() == to
+                && self.moved_piece(mv) == Some(piece)
+                && (promotion.is_none() || mv.promotion() == promotion)
+                && from_file.is_none_or(|file| mv.from().file() == file)
+                && from_rank.is_none_or(|rank| mv.from().rank() == rank)
+        });
+        match (candidates.next(), candidates.next()) {
+            (Some(mv), None) => Ok(mv),
+            (None, _) => Err(SanError::IllegalMoveError(s.to_string())),
+            (Some(_), Some(_)) => Err(SanError::AmbiguousMoveError(s.to_string()))
+        }
+    }
+
+    /// Returns the origin file, rank or square needed to tell `mv` apart from
+    /// other legal moves of the same piece type to the same square
+    pub(crate) fn disambiguation(&self, mv: &Move, piece: Piece) -> String {
+        let others: Vec<Move> = self.moves()
+            .into_iter()
+            .filter(|other| other.to() == mv.to() && other.from() != mv.from() && self.moved_piece(other) == Some(piece))
+            .collect();
+        if others.is_empty() {
+            return String::new();
+        }
+        let from = mv.from();
+        if others.iter().all(|other| other.from().file() != from.file()) {
+            ((b'a' + from.file() as u8) as char).to_string()
+        } else if others.iter().all(|other| other.from().rank() != from.rank()) {
+            ((b'1' + from.rank() as u8) as char).to_string()
+        } else {
+            from.to_string()
+        }
+    }
+
+    /// Returns `+` if `mv` gives check, `#` if it mates and nothing otherwise
+    pub(crate) fn check_suffix(&self, mv: &Move) -> &'static str {
+        let mut board = *self;
+        board.apply_move(mv);
+        if !board.in_check() {
+            ""
+        } else if board.moves().is_empty() {
+            "#"
+        } else {
+            "+"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::board::Board;
+    use crate::notation::san::SanError;
+
+    #[test]
+    fn test_san_opera_game() {
+        // Morphy vs Duke Karl / Count Isouard, Paris 1858
+        let uci = "e2e4 e7e5 g1f3 d7d6 d2d4 c8g4 d4e5 g4f3 d1f3 d6e5 f1c4 g8f6 f3b3 d8e7 b1c3 c7c6 c1g5 b7b5 c3b5 c6b5 \
+                   c4b5 b8d7 e1c1 a8d8 d1d7 d8d7 h1d1 e7e6 b5d7 f6d7 b3b8 d7b8 d1d8";
+        let san = "e4 e5 Nf3 d6 d4 Bg4 dxe5 Bxf3 Qxf3 dxe5 Bc4 Nf6 Qb3 Qe7 Nc3 c6 Bg5 b5 Nxb5 cxb5 \
+                   Bxb5+ Nbd7 O-O-O Rd8 Rxd7 Rxd7 Rd1 Qe6 Bxd7+ Nxd7 Qb8+ Nxb8 Rd8#";
+        let mut board = Board::default();
+        for (uci, san) in uci.split_whitespace().zip(san.split_whitespace()) {
+            let mv = board.parse_uci(uci).unwrap();
+            assert_eq!(board.san(&mv), san);
+            board.apply_move(&mv);
+        }
+    }
+
+    #[test]
+    fn test_san_disambiguation() {
+        let board = Board::from_str("7K/8/8/7k/8/Q7/8/Q1Q5 w - - 0 1").unwrap();
+        assert_eq!(board.san(&board.parse_uci("a1b2").unwrap()), "Qa1b2");
+        assert_eq!(board.san(&board.parse_uci("a3a2").unwrap()), "Q3a2");
+        assert_eq!(board.san(&board.parse_uci("c1b1").unwrap()), "Qcb1");
+
+        let board = Board::from_str("3r2k1/1P3ppp/8/8/8/8/5PPP/6K1 w - - 0 1").unwrap();
+        assert_eq!(board.san(&board.parse_uci("b7b8q").unwrap()), "b8=Q");
+        assert_eq!(board.san(&board.parse_uci("b7b8n").unwrap()), "b8=N");
+        assert_eq!(board.san(&board.parse_uci("g1f1").unwrap()), "Kf1");
+    }
+
+    #[test]
+    fn test_parse_san() {
+        let board = Board::from_str("r3k2r/1P6/8/3pP3/8/1N3N2/8/R3K2R w KQkq d6 0 1").unwrap();
+        let parse = |san| board.parse_san(san).map(|mv| mv.to_string());
+        assert_eq!(parse("0-0"), Ok(String::from("e1g1")));
+        assert_eq!(parse("O-O-O!?"), Ok(String::from("e1c1")));
+        assert_eq!(parse("Nfd4"), Ok(String::from("f3d4")));
+        assert_eq!(parse("Ng1"), Ok(String::from("f3g1")));
+        assert_eq!(parse("Nf3g1"), Ok(String::from("f3g1")));
+        assert_eq!(parse("exd6 e.p."), Ok(String::from("e5d6")));
+        assert_eq!(parse("bxa8Q"), Ok(String::from("b7a8q")));
+        assert_eq!(parse("bxa8=Q+"), Ok(String::from("b7a8q")));
+        assert_eq!(parse("b8(N)"), Ok(String::from("b7b8n")));
+        assert_eq!(parse("Nd4"), Err(SanError::AmbiguousMoveError(String::from("Nd4"))));
+        assert_eq!(parse("b8"), Err(SanError::AmbiguousMoveError(String::from("b8"))));
+        assert_eq!(parse("Nd5"), Err(SanError::IllegalMoveError(String::from("Nd5"))));
+        assert_eq!(parse("Zz9"), Err(SanError::SyntaxError(String::from("Zz9"))));
+    }
+}
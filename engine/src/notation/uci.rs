@@ -0,0 +1,89 @@
+use thiserror::Error;
+
+use types::moves::Move;
+use types::piece::{Piece, PromotionPiece};
+use types::square::Square;
+
+use crate::board::Board;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum UciMoveError {
+    #[error("Expected 4 or 5 characters, found {0}.")]
+    LengthError(usize),
+    #[error("{0} is not a valid square.")]
+    SquareError(String),
+    #[error("{0} is not a valid promotion piece.")]
+    PromotionError(char),
+    #[error("There is no piece on {0}.")]
+    EmptySquareError(Square),
+    #[error("{0} is not a legal move in this position.")]
+    IllegalMoveError(String),
+}
+
+impl Board {
+    /// Parses a move in UCI long algebraic notation, e.g. `e2e4` or `e7e8q`
+    ///
+    /// Castling is accepted both as the king's two-square step (`e1g1`) and as
+    /// the Chess960 king-takes-rook form (`e1h1`).
+    pub fn parse_uci(&self, s: &str) -> Result<Move, UciMoveError> {
+        if !s.is_ascii() || !(4..=5).contains(&s.len()) {
+            return Err(UciMoveError::LengthError(s.chars().count()));
+        }
+        let from: Square = s[0..2].parse().map_err(|_| UciMoveError::SquareError(s[0..2].to_string()))?;
+        let mut to: Square = s[2..4].parse().map_err(|_| UciMoveError::SquareError(s[2..4].to_string()))?;
+        let promotion = match s[4..].chars().next() {
+            None => None,
+            Some(c) => Some(match c.to_ascii_lowercase() {
+                'q' => PromotionPiece::Queen,
+                'r' => PromotionPiece::Rook,
+                'b' => PromotionPiece::Bishop,
+                'n' => PromotionPiece::Knight,
+                _ => return Err(UciMoveError::PromotionError(c))
+            })
+        };
+
+        let piece = self.piece_on(from).ok_or(UciMoveError::EmptySquareError(from))?;
+        if piece == Piece::King && self.piece_on(to) == Some(Piece::Rook) && !(to.bitboard() & self.us()).is_empty() {
+            to = match to.0 > from.0 {
+                true => Square(from.0 + 2),
+                false => Square(from.0 - 2)
+            };
+        }
+
+        self.moves()
+            .into_iter()
+            .find(|mv| mv.from() == from && mv.to() == to && mv.promotion() == promotion)
+            .ok_or(UciMoveError::IllegalMoveError(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use types::moves::MoveKind;
+    use types::piece::Piece;
+
+    use crate::board::Board;
+    use crate::notation::uci::UciMoveError;
+
+    #[test]
+    fn test_parse_uci() {
+        let board = Board::default();
+        let mv = board.parse_uci("e2e4").unwrap();
+        assert_eq!(mv.kind(), MoveKind::DoublePush);
+        assert_eq!(board.moved_piece(&mv), Some(Piece::Pawn));
+        assert_eq!(board.parse_uci("e2e5"), Err(UciMoveError::IllegalMoveError(String::from("e2e5"))));
+        assert!(board.parse_uci("e9e4").is_err());
+
+        let board = Board::from_str("r3k2r/1P6/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        assert_eq!(board.parse_uci("e1g1").unwrap().kind(), MoveKind::KingCastle);
+        assert_eq!(board.parse_uci("e1h1").unwrap().kind(), MoveKind::KingCastle);
+        assert_eq!(board.parse_uci("e1a1").unwrap().kind(), MoveKind::QueenCastle);
+
+        let mv = board.parse_uci("b7a8q").unwrap();
+        assert_eq!(mv.kind(), MoveKind::QueenPromotionCapture);
+        assert_eq!(board.captured_piece(&mv), Some(Piece::Rook));
+        assert_eq!(mv.to_string(), "b7a8q");
+    }
+}
@@ -0,0 +1,422 @@
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+use types::moves::{Move, MoveKind};
+
+use crate::board::Board;
+use crate::epd::{Epd, EpdError};
+
+/// Reference positions with published node counts
+pub const STANDARD_SUITE: &str = include_str!("../tests/perft/standard.epd");
+/// Positions reached by random playouts, guarding against regressions
+pub const RANDOM_SUITE: &str = include_str!("../tests/perft/random.epd");
+
+/// Counts the leaf nodes of the legal move tree up to `depth`
+pub fn perft(board: &Board, depth: usize) -> u64 {
+    perft_with(board, depth, true)
+}
+
+/// Counts the leaf nodes up to `depth`. With `bulk` the moves at depth one are
+/// counted without being made, otherwise every leaf move is applied and undone.
+pub fn perft_with(board: &Board, depth: usize, bulk: bool) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    if bulk && depth == 1 {
+        return board.count_moves();
+    }
+    let mut board = *board;
+    let mut nodes = 0;
+    for mv in board.moves() {
+        board.apply_move(&mv);
+        nodes += perft_with(&board, depth - 1, bulk);
+        board.undo_move(&mv);
+    }
+    nodes
+}
+
+/// Counts the same nodes as `perft`, spread over `threads` threads.
+///
+/// The tree is expanded until the frontier holds several positions per thread, the
+/// threads then take positions from it one at a time, each working on its own copy.
+pub fn perft_parallel(board: &Board, depth: usize, threads: usize) -> u64 {
+    parallel(board, depth, threads, perft)
+}
+
+fn parallel<F: Fn(&Board, usize) -> u64 + Sync>(board: &Board, depth: usize, threads: usize, count: F) -> u64 {
+    if depth < 2 || threads <= 1 {
+        return count(board, depth);
+    }
+    let mut frontier = vec![*board];
+    let mut remaining = depth;
+    while remaining > 1 && frontier.len() < 8 * threads {
+        frontier = frontier.iter().flat_map(|board| {
+            board.moves().iter().map(|mv| {
+                let mut child = *board;
+                child.apply_move(mv);
+                child
+            }).collect::<Vec<_>>()
+        }).collect();
+        remaining -= 1;
+    }
+
+    let next = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads).map(|_| scope.spawn(|| {
+            let mut nodes = 0;
+            while let Some(board) = frontier.get(next.fetch_add(1, Ordering::Relaxed)) {
+                nodes += count(board, remaining);
+            }
+            nodes
+        })).collect();
+        workers.into_iter().map(|worker| worker.join().expect("Perft thread panicked")).sum()
+    })
+}
+
+/// Fixed-size cache of perft results that can be shared between threads
+///
+/// Each entry holds the node count and depth in one word and their xor with the
+/// Zobrist key in the other, so torn writes and index collisions fail verification.
+pub struct PerftTable {
+    entries: Vec<[AtomicU64; 2]>,
+}
+
+impl PerftTable {
+    /// Allocates a table of at most `megabytes`, rounded down to a power of two entries
+    pub fn new(megabytes: usize) -> Self {
+        let capacity = (megabytes << 20) / std::mem::size_of::<[AtomicU64; 2]>();
+        let len = match capacity {
+            0 => 1,
+            n => 1 << n.ilog2()
+        };
+        PerftTable { entries: (0..len).map(|_| [AtomicU64::new(0), AtomicU64::new(0)]).collect() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&self) {
+        for [check, data] in &self.entries {
+            check.store(0, Ordering::Relaxed);
+            data.store(0, Ordering::Relaxed);
+        }
+    }
+
+    fn probe(&self, hash: u64, depth: usize) -> Option<u64> {
+        let [check, data] = &self.entries[hash as usize & (self.entries.len() - 1)];
+        let data = data.load(Ordering::Relaxed);
+        let valid = check.load(Ordering::Relaxed) ^ data == hash && data & 0xff == depth as u64;
+        valid.then_some(data >> 8)
+    }
+
+    fn store(&self, hash: u64, depth: usize, nodes: u64) {
+        let [check, data] = &self.entries[hash as usize & (self.entries.len() - 1)];
+        let value = nodes << 8 | depth as u64;
+        check.store(hash ^ value, Ordering::Relaxed);
+        data.store(value, Ordering::Relaxed);
+    }
+}
+
+/// Counts the same nodes as `perft`, caching the counts of subtrees in `table`
+pub fn perft_hashed(board: &Board, depth: usize, table: &PerftTable) -> u64 {
+    if depth <= 1 {
+        return perft(board, depth);
+    }
+    let hash = board.hash();
+    if let Some(nodes) = table.probe(hash, depth) {
+        return nodes;
+    }
+    let mut nodes = 0;
+    for mv in board.moves() {
+        let mut child = *board;
+        child.apply_move(&mv);
+        nodes += perft_hashed(&child, depth - 1, table);
+    }
+    table.store(hash, depth, nodes);
+    nodes
+}
+
+/// Runs `perft_hashed` on `threads` threads sharing one table
+pub fn perft_hashed_parallel(board: &Board, depth: usize, threads: usize, table: &PerftTable) -> u64 {
+    parallel(board, depth, threads, |board, depth| perft_hashed(board, depth, table))
+}
+
+/// Node count of a timed perft run
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PerftReport {
+    pub nodes: u64,
+    pub elapsed: Duration,
+}
+
+impl PerftReport {
+    /// Nodes per second
+    pub fn nps(&self) -> u64 {
+        match self.elapsed.as_secs_f64() {
+            secs if secs > 0.0 => (self.nodes as f64 / secs) as u64,
+            _ => 0
+        }
+    }
+}
+
+impl std::fmt::Display for PerftReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Nodes searched: {}\nTime: {:.3}s\nNPS: {}", self.nodes, self.elapsed.as_secs_f64(), self.nps())
+    }
+}
+
+/// Runs `perft_with` and measures how long it took
+pub fn perft_timed(board: &Board, depth: usize, bulk: bool) -> PerftReport {
+    let start = Instant::now();
+    let nodes = perft_with(board, depth, bulk);
+    PerftReport { nodes, elapsed: start.elapsed() }
+}
+
+/// Counts the leaf nodes below every root move, sorted by the UCI notation of the move
+pub fn divide(board: &Board, depth: usize) -> Vec<(Move, u64)> {
+    let mut counts: Vec<(Move, u64)> = board.moves().iter().map(|mv| {
+        let mut child = *board;
+        child.apply_move(mv);
+        (*mv, perft(&child, depth.saturating_sub(1)))
+    }).collect();
+    counts.sort_by_key(|(mv, _)| mv.to_string());
+    counts
+}
+
+/// Leaf statistics in the columns of the Chess Programming Wiki perft tables
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PerftStats {
+    pub nodes: u64,
+    pub captures: u64,
+    pub enpassant: u64,
+    pub castles: u64,
+    pub promotions: u64,
+    pub checks: u64,
+    /// Single checks given by a piece other than the moved one
+    pub discovered_checks: u64,
+    pub double_checks: u64,
+    pub checkmates: u64,
+}
+
+impl std::ops::AddAssign for PerftStats {
+    fn add_assign(&mut self, other: Self) {
+        self.nodes += other.nodes;
+        self.captures += other.captures;
+        self.enpassant += other.enpassant;
+        self.castles += other.castles;
+        self.promotions += other.promotions;
+        self.checks += other.checks;
+        self.discovered_checks += other.discovered_checks;
+        self.double_checks += other.double_checks;
+        self.checkmates += other.checkmates;
+    }
+}
+
+impl std::fmt::Display for PerftStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Nodes:            {}", self.nodes)?;
+        writeln!(f, "Captures:         {}", self.captures)?;
+        writeln!(f, "En passant:       {}", self.enpassant)?;
+        writeln!(f, "Castles:          {}", self.castles)?;
+        writeln!(f, "Promotions:       {}", self.promotions)?;
+        writeln!(f, "Checks:           {}", self.checks)?;
+        writeln!(f, "Discovery checks: {}", self.discovered_checks)?;
+        writeln!(f, "Double checks:    {}", self.double_checks)?;
+        write!(f, "Checkmates:       {}", self.checkmates)
+    }
+}
+
+/// Counts the leaf nodes up to `depth` and classifies the moves leading to them
+pub fn perft_stats(board: &Board, depth: usize) -> PerftStats {
+    let mut stats = PerftStats::default();
+    if depth == 0 {
+        stats.nodes = 1;
+        return stats;
+    }
+    for mv in board.moves() {
+        let mut child = *board;
+        child.apply_move(&mv);
+        if depth > 1 {
+            stats += perft_stats(&child, depth - 1);
+            continue;
+        }
+
+        stats.nodes += 1;
+        stats.captures += mv.is_capture() as u64;
+        stats.enpassant += (mv.kind() == MoveKind::EnPassant) as u64;
+        stats.castles += mv.is_castle() as u64;
+        stats.promotions += mv.is_promotion() as u64;
+        let checkers = child.checkers();
+        if !checkers.is_empty() {
+            stats.checks += 1;
+            match checkers.len() {
+                1 => stats.discovered_checks += (checkers != mv.to().bitboard()) as u64,
+                _ => stats.double_checks += 1
+            }
+            stats.checkmates += child.moves().is_empty() as u64;
+        }
+    }
+    stats
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum PerftError {
+    #[error("Line {0}: {1}")]
+    EpdError(usize, EpdError),
+    #[error("Line {line}: {fen} at depth {depth} gave {found} nodes instead of {expected}")]
+    CountError { line: usize, fen: String, depth: usize, expected: u64, found: u64 },
+}
+
+/// Parses a perft suite: one EPD record per line with `D1`..`D6` opcodes.
+/// Empty lines and lines starting with `#` are skipped.
+pub fn parse_suite(suite: &str) -> Result<Vec<(usize, Epd)>, PerftError> {
+    suite.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line, record)| Epd::from_str(record).map(|epd| (line, epd)).map_err(|e| PerftError::EpdError(line, e)))
+        .collect()
+}
+
+/// Verifies every count of the suite up to `max_depth` and returns the number of nodes visited.
+/// Stops at the first failing position and depth.
+pub fn run_suite(suite: &str, max_depth: usize) -> Result<u64, PerftError> {
+    let mut total = 0;
+    for (line, epd) in parse_suite(suite)? {
+        let mut depths: Vec<usize> = epd.opcodes.keys()
+            .filter_map(|opcode| opcode.strip_prefix('D')?.parse().ok())
+            .filter(|&depth| depth <= max_depth)
+            .collect();
+        depths.sort();
+        for depth in depths {
+            let Some(expected) = epd.perft(depth) else { continue };
+            let found = perft(&epd.board, depth);
+            if found != expected {
+                return Err(PerftError::CountError { line, fen: epd.board.fen(), depth, expected, found });
+            }
+            total += found;
+        }
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::board::{Board, STARTPOS};
+    use crate::perft::{divide, parse_suite, perft, perft_hashed, perft_hashed_parallel, perft_parallel, perft_stats, perft_timed, perft_with, run_suite, PerftError, PerftStats, PerftTable, RANDOM_SUITE, STANDARD_SUITE};
+
+    #[test]
+    fn test_perft_suite() {
+        if let Err(e) = run_suite(STANDARD_SUITE, 3) {
+            panic!("{e}");
+        }
+        if let Err(e) = run_suite(RANDOM_SUITE, 3) {
+            panic!("{e}");
+        }
+        assert!(parse_suite(RANDOM_SUITE).unwrap().len() >= 300);
+
+        let broken = "# Wrong count\n\n8/8/8/8/8/8/8/K6k w - - 0 1 ;D1 3 ;D2 10";
+        assert_eq!(run_suite(broken, 2).unwrap_err(), PerftError::CountError {
+            line: 3, fen: String::from("8/8/8/8/8/8/8/K6k w - - 0 1"), depth: 2, expected: 10, found: 9
+        });
+    }
+
+    #[test]
+    fn test_bulk_counting() {
+        // Promotions are counted four times in bulk as well
+        let board = Board::from_str("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1").unwrap();
+        for depth in 0..=3 {
+            assert_eq!(perft_with(&board, depth, true), perft_with(&board, depth, false));
+        }
+        let report = perft_timed(&board, 3, true);
+        assert_eq!(report.nodes, 9467);
+        assert!(report.to_string().starts_with("Nodes searched: 9467\n"));
+    }
+
+    #[test]
+    fn test_perft_parallel() {
+        let board = Board::from_str("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+        for threads in [1, 2, 3, 16] {
+            assert_eq!(perft_parallel(&board, 3, threads), 97862);
+        }
+        assert_eq!(perft_parallel(&board, 1, 4), 48);
+        let mate = Board::from_str("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3").unwrap();
+        assert_eq!(perft_parallel(&mate, 4, 4), 0);
+    }
+
+    #[test]
+    fn test_perft_hashed() {
+        let board = Board::from_str("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+        // A single entry forces every store to overwrite another position
+        let tiny = PerftTable::new(0);
+        assert_eq!(tiny.len(), 1);
+        assert_eq!(perft_hashed(&board, 4, &tiny), 4085603);
+
+        let table = PerftTable::new(1);
+        assert_eq!(table.len(), 1 << 16);
+        assert_eq!(perft_hashed(&board, 4, &table), 4085603);
+        assert_eq!(perft_hashed(&board, 4, &table), 4085603);
+        table.clear();
+        let start = Board::from_str(STARTPOS).unwrap();
+        assert_eq!(perft_hashed(&start, 5, &table), 4865609);
+        assert_eq!(perft_hashed_parallel(&start, 5, 4, &table), 4865609);
+    }
+
+    #[test]
+    fn test_divide() {
+        let board = Board::from_str(STARTPOS).unwrap();
+        let counts = divide(&board, 3);
+        assert_eq!(counts.len(), 20);
+        assert_eq!(counts[0].0.to_string(), "a2a3");
+        assert_eq!(counts.iter().find(|(mv, _)| mv.to_string() == "e2e4").unwrap().1, 600);
+        assert_eq!(counts.iter().map(|(_, nodes)| nodes).sum::<u64>(), perft(&board, 3));
+    }
+
+    #[test]
+    fn test_perft_stats() {
+        let board = Board::from_str("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+        assert_eq!(perft_stats(&board, 3), PerftStats {
+            nodes: 97862, captures: 17102, enpassant: 45, castles: 3162, promotions: 0,
+            checks: 993, discovered_checks: 0, double_checks: 0, checkmates: 1
+        });
+        let board = Board::from_str("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1").unwrap();
+        assert_eq!(perft_stats(&board, 4), PerftStats {
+            nodes: 43238, captures: 3348, enpassant: 123, castles: 0, promotions: 0,
+            checks: 1680, discovered_checks: 106, double_checks: 0, checkmates: 17
+        });
+    }
+
+    #[test]
+    #[ignore = "counts Kiwipete to depth 4 and position 3 to depth 5, use --release"]
+    fn test_perft_stats_deep() {
+        let board = Board::from_str("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+        assert_eq!(perft_stats(&board, 4), PerftStats {
+            nodes: 4085603, captures: 757163, enpassant: 1929, castles: 128013, promotions: 15172,
+            checks: 25523, discovered_checks: 42, double_checks: 6, checkmates: 43
+        });
+        let board = Board::from_str("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1").unwrap();
+        assert_eq!(perft_stats(&board, 5), PerftStats {
+            nodes: 674624, captures: 52051, enpassant: 1165, castles: 0, promotions: 0,
+            checks: 52950, discovered_checks: 1292, double_checks: 3, checkmates: 0
+        });
+    }
+
+    #[test]
+    #[ignore = "runs every count of the suites, use --release"]
+    fn test_perft_suite_full() {
+        for suite in [STANDARD_SUITE, RANDOM_SUITE] {
+            if let Err(e) = run_suite(suite, usize::MAX) {
+                panic!("{e}");
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use types::moves::Move;
+
+use crate::board::Board;
+
+pub mod reader;
+pub mod writer;
+
+pub use reader::{PgnError, PgnReader};
+
+/// Engine evaluation attached to a move with a `[%eval ...]` command
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Eval {
+    /// Score in pawns from White's point of view
+    Pawns(f32),
+    /// Mate in the given number of moves, negative if Black mates
+    Mate(i32),
+}
+
+/// A single move of a game together with its annotations
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    pub mv: Move,
+    /// Numeric annotation glyphs, `!` and `?` suffixes are stored as NAGs 1-6
+    pub nags: Vec<u8>,
+    /// Comments following the move
+    pub comments: Vec<String>,
+    /// Remaining clock time from a `[%clk ...]` command
+    pub clock: Option<Duration>,
+    pub eval: Option<Eval>,
+    /// Alternatives to this move
+    pub variations: Vec<Variation>,
+}
+
+impl Node {
+    pub fn new(mv: Move) -> Self {
+        Node {
+            mv,
+            nags: vec![],
+            comments: vec![],
+            clock: None,
+            eval: None,
+            variations: vec![]
+        }
+    }
+}
+
+/// A sequence of moves, either the main line or a variation
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Variation {
+    /// Comments preceding the first move
+    pub comments: Vec<String>,
+    pub nodes: Vec<Node>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Game {
+    /// Tag pairs in the order they were read
+    pub tags: Vec<(String, String)>,
+    /// Position the game starts from, taken from the `FEN` tag if present
+    pub board: Board,
+    pub mainline: Variation,
+    /// Game termination marker: `1-0`, `0-1`, `1/2-1/2` or `*`
+    pub result: String,
+}
+
+impl Game {
+    pub fn new(board: Board) -> Self {
+        Game {
+            tags: vec![],
+            board,
+            mainline: Variation::default(),
+            result: String::from("*")
+        }
+    }
+
+    /// Returns the value of the first tag named `name`
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the position after the last move of the main line
+    pub fn final_board(&self) -> Board {
+        let mut board = self.board;
+        for node in &self.mainline.nodes {
+            board.apply_move(&node.mv);
+        }
+        board
+    }
+}
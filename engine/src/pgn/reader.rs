@@ -0,0 +1,432 @@
+use std::io::BufRead;
+use std::str::FromStr;
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::board::Board;
+use crate::pgn::{Eval, Game, Node, Variation};
+
+#[derive(Debug, Error)]
+pub enum PgnErrorKind {
+    #[error("{0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Malformed tag pair: {0}")]
+    TagError(String),
+    #[error("Invalid FEN tag: {0}")]
+    FenError(String),
+    #[error("Unexpected token in movetext: {0}")]
+    SyntaxError(String),
+    #[error("Illegal or ambiguous move: {0}")]
+    MoveError(String),
+}
+
+/// Error for a game that could not be read, the reader continues with the next game
+#[derive(Debug, Error)]
+#[error("Game at byte {offset}: {kind}")]
+pub struct PgnError {
+    /// Byte offset of the first line of the broken game
+    pub offset: u64,
+    pub kind: PgnErrorKind,
+}
+
+/// Streams games from a PGN source one at a time
+///
+/// Only the text of the current game is kept in memory, so arbitrarily large
+/// databases can be read. Every move is replayed on a `Board`, a game that
+/// contains an illegal move is reported as an error and skipped.
+pub struct PgnReader<R: BufRead> {
+    reader: R,
+    offset: u64,
+    /// Tag line of the next game, read while looking for the end of the current one
+    pending: Option<(u64, String)>,
+    done: bool,
+}
+
+impl<R: BufRead> PgnReader<R> {
+    pub fn new(reader: R) -> Self {
+        PgnReader {
+            reader,
+            offset: 0,
+            pending: None,
+            done: false
+        }
+    }
+
+    fn read_line(&mut self) -> std::io::Result<Option<(u64, String)>> {
+        let mut buf = vec![];
+        let offset = self.offset;
+        let len = self.reader.read_until(b'\n', &mut buf)?;
+        if len == 0 {
+            return Ok(None);
+        }
+        self.offset += len as u64;
+        let mut line = String::from_utf8_lossy(&buf).into_owned();
+        if offset == 0 {
+            line = line.trim_start_matches('\u{feff}').to_string();
+        }
+        Ok(Some((offset, line)))
+    }
+
+    /// Collects the raw tag section and movetext of the next game
+    fn read_game(&mut self) -> Result<Option<(u64, Vec<String>, String)>, PgnError> {
+        let mut start = None;
+        let mut tags = vec![];
+        let mut movetext = String::new();
+        let mut in_comment = false;
+        // A blank line after the tags ends the tag section, even if no movetext follows
+        let mut separated = false;
+
+        loop {
+            let next = match self.pending.take() {
+                Some(line) => Some(line),
+                None => self.read_line().map_err(|err| PgnError { offset: self.offset, kind: err.into() })?
+            };
+            let Some((offset, line)) = next else {
+                self.done = true;
+                break;
+            };
+            let trimmed = line.trim();
+            // Lines starting with % are escaped and ignored
+            if line.starts_with('%') || (trimmed.is_empty() && start.is_none()) {
+                continue;
+            }
+            if trimmed.is_empty() {
+                separated = true;
+            }
+            if !in_comment && trimmed.starts_with('[') {
+                if separated || !movetext.trim().is_empty() {
+                    self.pending = Some((offset, line));
+                    break;
+                }
+                start.get_or_insert(offset);
+                tags.push(trimmed.to_string());
+                continue;
+            }
+            start.get_or_insert(offset);
+            for c in line.chars() {
+                match c {
+                    '{' => in_comment = true,
+                    '}' => in_comment = false,
+                    // The rest of the line is a comment, braces in it don't count
+                    ';' if !in_comment => break,
+                    _ => {}
+                }
+            }
+            movetext.push_str(&line);
+            if !line.ends_with('\n') {
+                movetext.push('\n');
+            }
+        }
+
+        Ok(start.map(|start| (start, tags, movetext)))
+    }
+}
+
+impl<R: BufRead> Iterator for PgnReader<R> {
+    type Item = Result<Game, PgnError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done && self.pending.is_none() {
+            return None;
+        }
+        match self.read_game() {
+            Ok(Some((offset, tags, movetext))) => Some(parse_game(&tags, &movetext).map_err(|kind| PgnError { offset, kind })),
+            Ok(None) => None,
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Parses a single `[Name "value"]` tag pair
+fn parse_tag(line: &str) -> Result<(String, String), PgnErrorKind> {
+    let error = || PgnErrorKind::TagError(line.to_string());
+    let inner = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')).ok_or_else(error)?.trim();
+    let (name, value) = inner.split_once(char::is_whitespace).ok_or_else(error)?;
+    let value = value.trim().strip_prefix('"').and_then(|value| value.strip_suffix('"')).ok_or_else(error)?;
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.push(chars.next().ok_or_else(error)?),
+            c => unescaped.push(c)
+        }
+    }
+    Ok((name.to_string(), unescaped))
+}
+
+enum Token<'a> {
+    Comment(&'a str),
+    Open,
+    Close,
+    Nag(u8),
+    Result(&'a str),
+    San(&'a str),
+}
+
+/// Splits movetext into tokens, dropping move numbers
+fn tokenize(movetext: &str) -> Result<Vec<Token<'_>>, PgnErrorKind> {
+    let mut tokens = vec![];
+    let mut rest = movetext;
+    loop {
+        rest = rest.trim_start();
+        let Some(c) = rest.chars().next() else {
+            break;
+        };
+        let (token, len) = match c {
+            '{' => {
+                let end = rest.find('}').ok_or(PgnErrorKind::SyntaxError(String::from("unterminated comment")))?;
+                (Some(Token::Comment(&rest[1..end])), end + 1)
+            }
+            ';' => {
+                let end = rest.find('\n').unwrap_or(rest.len());
+                (Some(Token::Comment(&rest[1..end])), end)
+            }
+            '(' => (Some(Token::Open), 1),
+            ')' => (Some(Token::Close), 1),
+            '$' => {
+                let end = rest[1..].find(|c: char| !c.is_ascii_digit()).map_or(rest.len(), |end| end + 1);
+                let nag = rest[1..end].parse().map_err(|_| PgnErrorKind::SyntaxError(rest[..end].to_string()))?;
+                (Some(Token::Nag(nag)), end)
+            }
+            _ => {
+                let end = rest.find(|c: char| c.is_whitespace() || "(){};$".contains(c)).unwrap_or(rest.len());
+                let word = &rest[..end];
+                // Move numbers may be glued to the move, as in 1.e4 or 12...Nf6
+                let san = word.trim_start_matches(|c: char| c.is_ascii_digit()).trim_start_matches('.');
+                let token = match word {
+                    "1-0" | "0-1" | "1/2-1/2" | "*" => Some(Token::Result(word)),
+                    // Castling written with zeros is not a move number
+                    _ if word.starts_with("0-0") => Some(Token::San(word)),
+                    _ if san.is_empty() => None,
+                    _ => Some(Token::San(san))
+                };
+                (token, end)
+            }
+        };
+        tokens.extend(token);
+        rest = &rest[len..];
+    }
+    Ok(tokens)
+}
+
+/// Splits `!`/`?` move suffixes off a SAN token and returns the matching NAG
+fn split_suffix(san: &str) -> (&str, Option<u8>) {
+    let stripped = san.trim_end_matches(['!', '?']);
+    let nag = match &san[stripped.len()..] {
+        "!" => Some(1),
+        "?" => Some(2),
+        "!!" => Some(3),
+        "??" => Some(4),
+        "!?" => Some(5),
+        "?!" => Some(6),
+        _ => None
+    };
+    (stripped, nag)
+}
+
+fn parse_clock(s: &str) -> Option<Duration> {
+    let mut seconds = 0.0;
+    for part in s.split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+fn parse_eval(s: &str) -> Option<Eval> {
+    match s.strip_prefix('#') {
+        Some(mate) => mate.parse().ok().map(Eval::Mate),
+        None => s.parse().ok().map(Eval::Pawns)
+    }
+}
+
+/// Removes `[%clk ...]` and `[%eval ...]` commands from a comment and stores them on `node`
+fn extract_commands(comment: &str, mut node: Option<&mut Node>) -> String {
+    let mut text = String::new();
+    let mut rest = comment;
+    while let Some(start) = rest.find("[%") {
+        let Some(end) = rest[start..].find(']') else {
+            break;
+        };
+        text.push_str(&rest[..start]);
+        let command = &rest[start + 2..start + end];
+        let (name, value) = command.split_once(char::is_whitespace).unwrap_or((command, ""));
+        match (name, node.as_deref_mut()) {
+            ("clk", Some(node)) => node.clock = parse_clock(value.trim()),
+            ("eval", Some(node)) => node.eval = parse_eval(value.trim()),
+            _ => text.push_str(&rest[start..=start + end])
+        }
+        rest = &rest[start + end + 1..];
+    }
+    text.push_str(rest);
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A line being read together with the positions needed to branch off it
+struct Frame {
+    variation: Variation,
+    board: Board,
+    /// Position before the last move, where a variation on that move starts
+    prev: Board,
+}
+
+fn parse_game(tag_lines: &[String], movetext: &str) -> Result<Game, PgnErrorKind> {
+    let mut tags = vec![];
+    for line in tag_lines {
+        tags.push(parse_tag(line)?);
+    }
+    let board = match tags.iter().find(|(name, _)| name == "FEN") {
+        Some((_, fen)) => Board::from_str(fen).map_err(|_| PgnErrorKind::FenError(fen.clone()))?,
+        None => Board::default()
+    };
+    let mut game = Game::new(board);
+    game.tags = tags;
+    if let Some(result) = game.tag("Result") {
+        game.result = result.to_string();
+    }
+
+    let mut stack = vec![Frame { variation: Variation::default(), board, prev: board }];
+    for token in tokenize(movetext)? {
+        let frame = stack.last_mut().expect("frame stack is never empty");
+        match token {
+            Token::Comment(comment) => {
+                let comment = extract_commands(comment, frame.variation.nodes.last_mut());
+                if comment.is_empty() {
+                    continue;
+                }
+                match frame.variation.nodes.last_mut() {
+                    Some(node) => node.comments.push(comment),
+                    None => frame.variation.comments.push(comment)
+                }
+            }
+            Token::Nag(nag) => match frame.variation.nodes.last_mut() {
+                Some(node) => node.nags.push(nag),
+                None => return Err(PgnErrorKind::SyntaxError(format!("${nag}")))
+            },
+            Token::San(token) => {
+                let (san, nag) = split_suffix(token);
+                let mv = frame.board.parse_san(san).map_err(|_| PgnErrorKind::MoveError(token.to_string()))?;
+                let mut node = Node::new(mv);
+                node.nags.extend(nag);
+                frame.variation.nodes.push(node);
+                frame.prev = frame.board;
+                frame.board.apply_move(&mv);
+            }
+            Token::Open => {
+                if frame.variation.nodes.is_empty() {
+                    return Err(PgnErrorKind::SyntaxError(String::from("(")));
+                }
+                let prev = frame.prev;
+                stack.push(Frame { variation: Variation::default(), board: prev, prev });
+            }
+            Token::Close => {
+                if stack.len() == 1 {
+                    return Err(PgnErrorKind::SyntaxError(String::from(")")));
+                }
+                let variation = stack.pop().map(|frame| frame.variation).unwrap_or_default();
+                if let Some(node) = stack.last_mut().and_then(|frame| frame.variation.nodes.last_mut()) {
+                    node.variations.push(variation);
+                }
+            }
+            Token::Result(result) => {
+                if stack.len() > 1 {
+                    return Err(PgnErrorKind::SyntaxError(result.to_string()));
+                }
+                game.result = result.to_string();
+            }
+        }
+    }
+
+    if stack.len() > 1 {
+        return Err(PgnErrorKind::SyntaxError(String::from("unterminated variation")));
+    }
+    game.mainline = stack.pop().map(|frame| frame.variation).unwrap_or_default();
+    Ok(game)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::pgn::{Eval, PgnReader};
+    use crate::pgn::reader::PgnErrorKind;
+
+    const PGN: &str = r#"[Event "Paris"]
+[White "Morphy, Paul"]
+[Black "Duke Karl / Count Isouard"]
+[Result "1-0"]
+
+1. e4 e5 2. Nf3 d6 3. d4 Bg4 {This is a weak move already.} 4. dxe5 Bxf3 5. Qxf3
+dxe5 6. Bc4 Nf6 7. Qb3 Qe7 8. Nc3 c6 9. Bg5 b5?! 10. Nxb5! cxb5 11. Bxb5+ Nbd7
+12. O-O-O Rd8 13. Rxd7 Rxd7 14. Rd1 Qe6 15. Bxd7+ Nxd7 16. Qb8+ Nxb8 17. Rd8# 1-0
+
+[Event "Broken"]
+[Result "*"]
+
+1. e4 e5 2. Ke3 *
+
+[Event "Annotated"]
+[FEN "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"]
+[SetUp "1"]
+
+{Start} 1. e4 {[%clk 0:03:00] [%eval 0.17] book} (1. Kd2 $2 ; Slow
+Kd7 (1... Ke7 2. e4)) 1... Kd7 {[%eval #-3]} 2. e5 {[%clk -5]} *
+"#;
+
+    #[test]
+    fn test_pgn_reader() {
+        let mut reader = PgnReader::new(PGN.as_bytes());
+
+        let game = reader.next().unwrap().unwrap();
+        assert_eq!(game.tag("White"), Some("Morphy, Paul"));
+        assert_eq!(game.mainline.nodes.len(), 33);
+        assert_eq!(game.mainline.nodes[5].comments, vec![String::from("This is a weak move already.")]);
+        assert_eq!(game.mainline.nodes[17].nags, vec![6]);
+        assert_eq!(game.result, "1-0");
+        assert!(game.final_board().moves().is_empty());
+
+        let err = reader.next().unwrap().unwrap_err();
+        assert_eq!(err.offset, PGN.find("[Event \"Broken\"]").unwrap() as u64);
+        assert!(matches!(err.kind, PgnErrorKind::MoveError(ref san) if san == "Ke3"));
+
+        let game = reader.next().unwrap().unwrap();
+        assert_eq!(game.mainline.comments, vec![String::from("Start")]);
+        let first = &game.mainline.nodes[0];
+        assert_eq!(first.clock, Some(Duration::from_secs(180)));
+        assert_eq!(first.eval, Some(Eval::Pawns(0.17)));
+        assert_eq!(first.comments, vec![String::from("book")]);
+        assert_eq!(first.variations.len(), 1);
+        let variation = &first.variations[0];
+        assert_eq!(variation.nodes[0].nags, vec![2]);
+        assert_eq!(variation.nodes[0].comments, vec![String::from("Slow")]);
+        assert_eq!(variation.nodes[1].variations[0].nodes.len(), 2);
+        assert_eq!(game.mainline.nodes[1].eval, Some(Eval::Mate(-3)));
+        assert_eq!(game.mainline.nodes.len(), 3);
+        // A negative clock is dropped instead of panicking
+        assert_eq!(game.mainline.nodes[2].clock, None);
+
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_game_boundaries() {
+        // A brace inside a rest-of-line comment doesn't open a comment
+        let pgn = "[Event \"A\"]\n\n1. e4 ; a { here\n1... e5 *\n\n[Event \"B\"]\n\n1. d4 *\n";
+        let games: Vec<_> = PgnReader::new(pgn.as_bytes()).map(Result::unwrap).collect();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].mainline.nodes.len(), 2);
+        assert_eq!(games[0].mainline.nodes[0].comments, vec![String::from("a { here")]);
+        assert_eq!(games[1].tag("Event"), Some("B"));
+
+        // A game without movetext keeps its own tags
+        let pgn = "[Event \"A\"]\n[Result \"*\"]\n\n[Event \"B\"]\n\n1. d4 *\n";
+        let games: Vec<_> = PgnReader::new(pgn.as_bytes()).map(Result::unwrap).collect();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].tags.len(), 2);
+        assert!(games[0].mainline.nodes.is_empty());
+        assert_eq!(games[1].tags, vec![(String::from("Event"), String::from("B"))]);
+        assert_eq!(games[1].mainline.nodes.len(), 1);
+    }
+}
@@ -0,0 +1,246 @@
+use std::time::Duration;
+
+use types::color::Color;
+
+use crate::board::{Board, STARTPOS};
+use crate::pgn::{Eval, Game, Node, Variation};
+
+/// Tags every exported game starts with, in this order
+pub const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+/// Maximum length of a movetext line
+const LINE_WIDTH: usize = 80;
+
+fn format_clock(clock: Duration) -> String {
+    let seconds = clock.as_secs();
+    let tenths = clock.subsec_millis() / 100;
+    let mut str = format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if tenths > 0 {
+        str.push_str(&format!(".{tenths}"));
+    }
+    str
+}
+
+fn format_eval(eval: Eval) -> String {
+    match eval {
+        // The shortest form that reads back as the same value, so 1.5 stays 1.5
+        Eval::Pawns(pawns) => format!("{pawns}"),
+        Eval::Mate(moves) => format!("#{moves}")
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Collects movetext tokens so they can be wrapped afterwards
+#[derive(Default)]
+struct Movetext {
+    tokens: Vec<String>,
+    /// Opening parenthesis waiting to be glued to the next token
+    open: bool,
+    /// Whether the next Black move needs its own move number
+    number_black: bool,
+}
+
+impl Movetext {
+    fn push(&mut self, token: String) {
+        match std::mem::take(&mut self.open) {
+            true => self.tokens.push(format!("({token}")),
+            false => self.tokens.push(token)
+        }
+    }
+
+    fn push_comment(&mut self, comment: &str) {
+        // A closing brace would end the comment early, and PGN has no escape for it
+        let comment = comment.replace('}', "");
+        for (i, word) in comment.split_whitespace().enumerate() {
+            self.push(if i == 0 { format!("{{{word}") } else { word.to_string() });
+        }
+        match self.tokens.last_mut() {
+            Some(last) if !comment.trim().is_empty() => last.push('}'),
+            _ => self.push(String::from("{}"))
+        }
+        self.number_black = true;
+    }
+
+    fn push_node(&mut self, board: &Board, node: &Node) {
+        // Move numbers stay on the same line as their move
+        let san = board.san(&node.mv);
+        match board.turn {
+            Color::White => self.push(format!("{}. {san}", board.fullmoves)),
+            Color::Black if self.number_black => self.push(format!("{}... {san}", board.fullmoves)),
+            Color::Black => self.push(san)
+        }
+        self.number_black = false;
+        for nag in &node.nags {
+            self.push(format!("${nag}"));
+        }
+
+        let mut commands = vec![];
+        if let Some(clock) = node.clock {
+            commands.push(format!("[%clk {}]", format_clock(clock)));
+        }
+        if let Some(eval) = node.eval {
+            commands.push(format!("[%eval {}]", format_eval(eval)));
+        }
+        match node.comments.split_first() {
+            Some((first, rest)) => {
+                commands.push(first.clone());
+                self.push_comment(&commands.join(" "));
+                for comment in rest {
+                    self.push_comment(comment);
+                }
+            }
+            None if !commands.is_empty() => self.push_comment(&commands.join(" ")),
+            None => {}
+        }
+
+        for variation in &node.variations {
+            self.push_variation(board, variation, true);
+        }
+    }
+
+    fn push_variation(&mut self, board: &Board, variation: &Variation, nested: bool) {
+        if nested {
+            self.open = true;
+        }
+        self.number_black = true;
+        for comment in &variation.comments {
+            self.push_comment(comment);
+        }
+        let mut board = *board;
+        for node in &variation.nodes {
+            self.push_node(&board, node);
+            board.apply_move(&node.mv);
+        }
+        if nested {
+            match self.tokens.last_mut() {
+                Some(last) if !self.open => last.push(')'),
+                _ => {
+                    self.open = false;
+                    self.tokens.push(String::from("()"));
+                }
+            }
+            self.number_black = true;
+        }
+    }
+
+    fn wrap(&self) -> String {
+        let mut str = String::new();
+        let mut line = 0;
+        for token in &self.tokens {
+            if line > 0 && line + 1 + token.len() > LINE_WIDTH {
+                str.push('\n');
+                line = 0;
+            } else if line > 0 {
+                str.push(' ');
+                line += 1;
+            }
+            str.push_str(token);
+            line += token.len();
+        }
+        str
+    }
+}
+
+/// Exports the game as PGN: the Seven Tag Roster, any other tags, and the
+/// movetext wrapped at 80 columns, followed by an empty line
+impl std::fmt::Display for Game {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, default) in SEVEN_TAG_ROSTER {
+            let value = match name {
+                "Result" => self.result.as_str(),
+                _ => self.tag(name).unwrap_or(default)
+            };
+            writeln!(f, "[{name} \"{}\"]", escape(value))?;
+        }
+        let fen = self.board.fen();
+        if fen != STARTPOS {
+            writeln!(f, "[SetUp \"1\"]")?;
+            writeln!(f, "[FEN \"{fen}\"]")?;
+        }
+        for (name, value) in &self.tags {
+            if !SEVEN_TAG_ROSTER.iter().any(|(tag, _)| tag == name) && name != "SetUp" && name != "FEN" {
+                writeln!(f, "[{name} \"{}\"]", escape(value))?;
+            }
+        }
+        writeln!(f)?;
+
+        let mut movetext = Movetext::default();
+        movetext.push_variation(&self.board, &self.mainline, false);
+        movetext.push(self.result.clone());
+        writeln!(f, "{}", movetext.wrap())?;
+        writeln!(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pgn::{Eval, PgnReader};
+
+    const PGN: &str = r#"[Event "Paris"]
+[Site "Paris FRA"]
+[Date "1858.??.??"]
+[Round "?"]
+[White "Morphy, Paul"]
+[Black "Duke Karl / Count Isouard"]
+[Result "1-0"]
+[ECO "C41"]
+
+1. e4 e5 2. Nf3 d6 3. d4 Bg4 {This is a weak move already.} 4. dxe5 Bxf3 5. Qxf3
+dxe5 6. Bc4 Nf6 7. Qb3 Qe7 8. Nc3 c6 9. Bg5 b5 $6 (9... Qb4 10. Qxb4 Bxb4
+11. O-O (11. O-O-O)) 10. Nxb5 $1 cxb5 11. Bxb5+ Nbd7 12. O-O-O Rd8 13. Rxd7 Rxd7
+14. Rd1 Qe6 15. Bxd7+ Nxd7 16. Qb8+ Nxb8 17. Rd8# 1-0
+
+[Event "?"]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "?"]
+[Black "?"]
+[Result "*"]
+[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/4P3/4K3 b - - 0 1"]
+
+{Start} 1... Kd7 {[%clk 0:03:00] [%eval 0.17] book} (1... Ke7 2. e4 $2 {Slow})
+2. e4 {[%eval #-3]} *
+
+"#;
+
+    #[test]
+    fn test_pgn_round_trip() {
+        let exported: String = PgnReader::new(PGN.as_bytes()).map(|game| game.unwrap().to_string()).collect();
+        assert_eq!(exported, PGN);
+    }
+
+    #[test]
+    fn test_pgn_writer_canonicalizes() {
+        let pgn = "[White \"Morphy\"]\n\n1.e4 e5!? 2.Nf3 {a   comment} 1-0";
+        let game = PgnReader::new(pgn.as_bytes()).next().unwrap().unwrap();
+        assert_eq!(game.to_string(), "[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n\
+            [White \"Morphy\"]\n[Black \"?\"]\n[Result \"1-0\"]\n\n1. e4 e5 $5 2. Nf3 {a comment} 1-0\n\n");
+    }
+
+    #[test]
+    fn test_pgn_writer_comments() {
+        let pgn = "1. e4 {[%eval 1.5] fine} 1... e5 {[%eval -0.25]} 2. Nf3 {[%eval 12]} *";
+        let mut game = PgnReader::new(pgn.as_bytes()).next().unwrap().unwrap();
+        game.mainline.nodes[1].comments.push(String::from("a } brace"));
+        let exported = game.to_string();
+        assert!(exported.ends_with("\n\n1. e4 {[%eval 1.5] fine} 1... e5 {[%eval -0.25] a brace} 2. Nf3 {[%eval 12]} *\n\n"), "{exported}");
+
+        let reread = PgnReader::new(exported.as_bytes()).next().unwrap().unwrap();
+        let evals: Vec<_> = reread.mainline.nodes.iter().map(|node| node.eval).collect();
+        assert_eq!(evals, vec![Some(Eval::Pawns(1.5)), Some(Eval::Pawns(-0.25)), Some(Eval::Pawns(12.0))]);
+        assert_eq!(reread.mainline.nodes[1].comments, vec![String::from("a brace")]);
+    }
+}
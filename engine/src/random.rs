@@ -0,0 +1,249 @@
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use types::bitboard::BitBoard;
+use types::color::Color;
+use types::moves::Move;
+use types::nonsliders::common::KING_MOVES;
+use types::piece::Piece;
+
+use crate::board::{Board, PIECES, STARTPOS};
+
+/// Maximum number of placements tried before giving up on a material set
+const PLACEMENT_ATTEMPTS: usize = 10_000;
+
+/// Small seedable SplitMix64 generator, so random positions are reproducible
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub const fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `0..n`
+    pub fn below(&mut self, n: usize) -> usize {
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        match items.is_empty() {
+            true => None,
+            false => Some(&items[self.below(items.len())])
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum RandomPositionError {
+    #[error("Material contains invalid symbol: {0}")]
+    SymbolError(char),
+    #[error("Both sides need exactly one king.")]
+    KingError,
+    #[error("Material does not fit on the board.")]
+    SizeError,
+    #[error("No legal placement found for the material.")]
+    PlacementError,
+}
+
+/// Pieces of both sides, indexed like `Board::pieces`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Material {
+    pub counts: [[u8; 6]; 2],
+}
+
+impl FromStr for Material {
+    type Err = RandomPositionError;
+    /// Parses piece letters as in FEN, e.g. `KRPkr`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut counts = [[0u8; 6]; 2];
+        for c in s.chars() {
+            let color = if c.is_ascii_uppercase() { 0 } else { 1 };
+            let piece = match c.to_ascii_uppercase() {
+                'K' => 0,
+                'Q' => 1,
+                'R' => 2,
+                'B' => 3,
+                'N' => 4,
+                'P' => 5,
+                _ => return Err(RandomPositionError::SymbolError(c))
+            };
+            counts[color][piece] = counts[color][piece].checked_add(1).ok_or(RandomPositionError::SizeError)?;
+        }
+        Ok(Material { counts })
+    }
+}
+
+impl Board {
+    /// Plays up to `plies` uniformly random legal moves, stopping early at checkmate or stalemate
+    pub fn random_playout(&self, plies: usize, rng: &mut Rng) -> (Board, Vec<Move>) {
+        let mut board = *self;
+        let mut played = vec![];
+        for _ in 0..plies {
+            let moves = board.moves();
+            let Some(mv) = rng.choose(&moves) else { break };
+            board.apply_move(mv);
+            played.push(*mv);
+        }
+        (board, played)
+    }
+
+    /// Returns the position after `plies` random moves from the starting position
+    pub fn random_position(plies: usize, seed: u64) -> Board {
+        let board = Board::from_str(STARTPOS).expect("Error parsing FEN");
+        board.random_playout(plies, &mut Rng::new(seed)).0
+    }
+
+    /// Places the material on random squares, keeping pawns off the first and last rank,
+    /// the side not to move out of check and the side to move out of double checks no
+    /// move could give. The side to move is random unless given.
+    pub fn random_placement(material: &Material, turn: Option<Color>, rng: &mut Rng) -> Result<Board, RandomPositionError> {
+        if material.counts[0][0] != 1 || material.counts[1][0] != 1 {
+            return Err(RandomPositionError::KingError);
+        }
+        let total: usize = material.counts.iter().flatten().map(|&n| n as usize).sum();
+        let pawns = material.counts[0][5] as usize + material.counts[1][5] as usize;
+        if total > 64 || pawns > 48 {
+            return Err(RandomPositionError::SizeError);
+        }
+
+        for _ in 0..PLACEMENT_ATTEMPTS {
+            let mut squares: [Option<char>; 64] = [None; 64];
+            // Pawns are restricted the most, so they are placed first
+            for piece in PIECES.iter().rev() {
+                for color in 0..2 {
+                    for _ in 0..material.counts[color][*piece as usize] {
+                        let sq = loop {
+                            let sq = match piece {
+                                Piece::Pawn => 8 + rng.below(48),
+                                _ => rng.below(64)
+                            };
+                            if squares[sq].is_none() {
+                                break sq;
+                            }
+                        };
+                        let letter = piece.to_string().chars().next().unwrap_or('P');
+                        squares[sq] = Some(if color == 0 { letter } else { letter.to_ascii_lowercase() });
+                    }
+                }
+            }
+
+            let turn = turn.unwrap_or(if rng.below(2) == 0 { Color::White } else { Color::Black });
+            let mut board = Board::from_str(&placement_fen(&squares, turn)).map_err(|_| RandomPositionError::PlacementError)?;
+            // Kings never give check, so adjacent kings have to be ruled out separately
+            let king = board.pieces[0][0].0.trailing_zeros() as usize;
+            let adjacent = !(BitBoard(KING_MOVES[king]) & board.pieces[1][0]).is_empty();
+            board.turn = !turn;
+            if adjacent || board.in_check() {
+                continue;
+            }
+            board.turn = turn;
+            // Only a discovered check by a slider can come with a second checker
+            let checkers = board.checkers();
+            let sliders = board.pieces[!turn as usize][1] | board.pieces[!turn as usize][2] | board.pieces[!turn as usize][3];
+            if checkers.len() > 2 || (checkers.len() == 2 && (checkers & sliders).is_empty()) {
+                continue;
+            }
+            return Ok(board);
+        }
+        Err(RandomPositionError::PlacementError)
+    }
+}
+
+fn placement_fen(squares: &[Option<char>; 64], turn: Color) -> String {
+    let rows: Vec<String> = (0..8).rev().map(|rank| {
+        let mut row = String::new();
+        let mut empty = 0;
+        for file in 0..8 {
+            match squares[8 * rank + file] {
+                Some(c) => {
+                    if empty > 0 {
+                        row.push_str(&empty.to_string());
+                        empty = 0;
+                    }
+                    row.push(c);
+                }
+                None => empty += 1
+            }
+        }
+        if empty > 0 {
+            row.push_str(&empty.to_string());
+        }
+        row
+    }).collect();
+    let turn = if turn == Color::White { "w" } else { "b" };
+    format!("{} {turn} - - 0 1", rows.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use types::bitboard::BitBoard;
+    use types::color::Color;
+    use types::piece::Piece;
+
+    use crate::board::{Board, STARTPOS};
+    use crate::random::{Material, RandomPositionError, Rng};
+
+    #[test]
+    fn test_random_playout() {
+        let start = Board::from_str(STARTPOS).unwrap();
+        let (board, moves) = start.random_playout(200, &mut Rng::new(7));
+        assert_eq!(Board::random_position(200, 7).fen(), board.fen());
+        assert_ne!(Board::random_position(200, 8).fen(), board.fen());
+
+        let mut replay = start;
+        for mv in &moves {
+            assert!(replay.moves().contains(mv));
+            replay.apply_move(mv);
+        }
+        assert_eq!(replay.fen(), board.fen());
+        assert!(moves.len() == 200 || board.moves().is_empty());
+    }
+
+    #[test]
+    fn test_random_placement() {
+        let material = Material::from_str("KQRPPkrbn").unwrap();
+        let kings = Material::from_str("Kk").unwrap();
+        let mut rng = Rng::new(42);
+        for i in 0..400 {
+            let material = if i % 2 == 0 { material } else { kings };
+            let board = Board::random_placement(&material, Some(Color::Black), &mut rng).unwrap();
+            assert_eq!(board.turn, Color::Black);
+            assert_eq!(board.occupied().len(), if i % 2 == 0 { 9 } else { 2 });
+            assert!((board.pieces[0][5] & BitBoard(0xff000000000000ff)).is_empty());
+            let mut other = board;
+            other.turn = Color::White;
+            assert!(!other.in_check());
+            assert!(board.moves().iter().all(|mv| board.squares[mv.to().0] != Some(Piece::King)));
+        }
+
+        // Two knights or pawns can never give check at the same time
+        let leapers = Material::from_str("KNNPPk").unwrap();
+        for _ in 0..2000 {
+            let board = Board::random_placement(&leapers, Some(Color::Black), &mut rng).unwrap();
+            assert!(board.checkers().len() < 2, "{}", board.fen());
+        }
+
+        let first = Board::random_placement(&material, None, &mut Rng::new(1)).unwrap();
+        let second = Board::random_placement(&material, None, &mut Rng::new(1)).unwrap();
+        assert_eq!(first.fen(), second.fen());
+
+        assert_eq!(Material::from_str("KQkx").unwrap_err(), RandomPositionError::SymbolError('x'));
+        assert_eq!(Material::from_str(&"Q".repeat(256)).unwrap_err(), RandomPositionError::SizeError);
+        assert_eq!(Board::random_placement(&Material::from_str(&format!("K{}k", "Q".repeat(255))).unwrap(), None, &mut rng).unwrap_err(), RandomPositionError::SizeError);
+        assert_eq!(Board::random_placement(&Material::from_str("KQ").unwrap(), None, &mut rng).unwrap_err(), RandomPositionError::KingError);
+    }
+}
@@ -0,0 +1,360 @@
+use types::color::Color;
+use types::moves::{Move, MoveKind};
+use types::piece::{Piece, PromotionPiece};
+use types::square::Square;
+
+/// Deliberately simple mailbox move generator used to cross-check `Board::moves`.
+///
+/// It shares no code with the bitboard generator: it parses FEN on its own, walks
+/// rays square by square and finds legal moves by making every pseudo-legal move
+/// and testing whether the king is attacked afterwards.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mailbox {
+    pub squares: [Option<(Color, Piece)>; 64],
+    pub turn: Color,
+    /// White kingside, White queenside, Black kingside, Black queenside
+    pub castling: [bool; 4],
+    pub enpassant: Option<usize>,
+}
+
+const KNIGHT: [(i32, i32); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+const KING: [(i32, i32); 8] = [(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)];
+const ORTHOGONAL: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+const DIAGONAL: [(i32, i32); 4] = [(1, 1), (-1, 1), (-1, -1), (1, -1)];
+
+/// Returns the square `df` files and `dr` ranks away, if it is on the board
+fn offset(sq: usize, df: i32, dr: i32) -> Option<usize> {
+    let (file, rank) = ((sq % 8) as i32 + df, (sq / 8) as i32 + dr);
+    ((0..8).contains(&file) && (0..8).contains(&rank)).then_some((8 * rank + file) as usize)
+}
+
+fn forward(color: Color) -> i32 {
+    match color {
+        Color::White => 1,
+        Color::Black => -1
+    }
+}
+
+impl Mailbox {
+    /// Parses a FEN, ignoring the clocks
+    pub fn from_fen(fen: &str) -> Option<Mailbox> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() < 4 {
+            return None;
+        }
+        let mut squares = [None; 64];
+        let rows: Vec<&str> = fields[0].split('/').collect();
+        if rows.len() != 8 {
+            return None;
+        }
+        for (i, row) in rows.iter().enumerate() {
+            let rank = 7 - i;
+            let mut file = 0;
+            for c in row.chars() {
+                if let Some(empty) = c.to_digit(10) {
+                    file += empty as usize;
+                    continue;
+                }
+                let color = if c.is_ascii_uppercase() { Color::White } else { Color::Black };
+                let piece = match c.to_ascii_lowercase() {
+                    'k' => Piece::King,
+                    'q' => Piece::Queen,
+                    'r' => Piece::Rook,
+                    'b' => Piece::Bishop,
+                    'n' => Piece::Knight,
+                    'p' => Piece::Pawn,
+                    _ => return None
+                };
+                if file >= 8 {
+                    return None;
+                }
+                squares[8 * rank + file] = Some((color, piece));
+                file += 1;
+            }
+            if file != 8 {
+                return None;
+            }
+        }
+        let turn = match fields[1] {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return None
+        };
+
+        let mut mailbox = Mailbox { squares, turn, castling: [false; 4], enpassant: None };
+        // A right only counts while the king and rook still stand on their squares
+        for (i, (c, king, rook, color)) in [('K', 4, 7, Color::White), ('Q', 4, 0, Color::White), ('k', 60, 63, Color::Black), ('q', 60, 56, Color::Black)].into_iter().enumerate() {
+            mailbox.castling[i] = fields[2].contains(c)
+                && squares[king] == Some((color, Piece::King))
+                && squares[rook] == Some((color, Piece::Rook));
+        }
+        if fields[3] != "-" {
+            let bytes = fields[3].as_bytes();
+            if bytes.len() != 2 || !(b'a'..=b'h').contains(&bytes[0]) || !(b'1'..=b'8').contains(&bytes[1]) {
+                return None;
+            }
+            let sq = 8 * (bytes[1] - b'1') as usize + (bytes[0] - b'a') as usize;
+            // The pawn that just moved has to stand in front of the target square
+            let pawn = offset(sq, 0, forward(!turn))?;
+            if squares[pawn] == Some((!turn, Piece::Pawn)) {
+                mailbox.enpassant = Some(sq);
+            }
+        }
+        Some(mailbox)
+    }
+
+    /// Returns the position as FEN with zeroed clocks
+    pub fn fen(&self) -> String {
+        let mut fen = String::new();
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                match self.squares[8 * rank + file] {
+                    Some((color, piece)) => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        let letter = piece.to_string();
+                        fen.push_str(&if color == Color::White { letter } else { letter.to_lowercase() });
+                    }
+                    None => empty += 1
+                }
+            }
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+            if rank > 0 {
+                fen.push('/');
+            }
+        }
+        fen.push_str(if self.turn == Color::White { " w " } else { " b " });
+        let castling: String = self.castling.iter().zip("KQkq".chars()).filter_map(|(&allowed, c)| allowed.then_some(c)).collect();
+        fen.push_str(if castling.is_empty() { "-" } else { &castling });
+        match self.enpassant {
+            Some(sq) => fen.push_str(&format!(" {} 0 1", Square(sq))),
+            None => fen.push_str(" - 0 1")
+        }
+        fen
+    }
+
+    fn king(&self, color: Color) -> Option<usize> {
+        self.squares.iter().position(|&sq| sq == Some((color, Piece::King)))
+    }
+
+    /// Specifies whether `by` attacks `sq`
+    pub fn attacked(&self, sq: usize, by: Color) -> bool {
+        let holds = |target: Option<usize>, pieces: &[Piece]| {
+            target.and_then(|t| self.squares[t]).is_some_and(|(color, piece)| color == by && pieces.contains(&piece))
+        };
+        let pawn = -forward(by);
+        if holds(offset(sq, -1, pawn), &[Piece::Pawn]) || holds(offset(sq, 1, pawn), &[Piece::Pawn]) {
+            return true;
+        }
+        if KNIGHT.iter().any(|&(df, dr)| holds(offset(sq, df, dr), &[Piece::Knight])) {
+            return true;
+        }
+        if KING.iter().any(|&(df, dr)| holds(offset(sq, df, dr), &[Piece::King])) {
+            return true;
+        }
+        for (directions, sliders) in [(ORTHOGONAL, [Piece::Rook, Piece::Queen]), (DIAGONAL, [Piece::Bishop, Piece::Queen])] {
+            for (df, dr) in directions {
+                let mut target = offset(sq, df, dr);
+                while let Some(t) = target {
+                    if self.squares[t].is_some() {
+                        if holds(Some(t), &sliders) {
+                            return true;
+                        }
+                        break;
+                    }
+                    target = offset(t, df, dr);
+                }
+            }
+        }
+        false
+    }
+
+    /// Specifies whether the side to move is in check
+    pub fn in_check(&self) -> bool {
+        self.king(self.turn).is_some_and(|king| self.attacked(king, !self.turn))
+    }
+
+    fn pseudo_legal(&self) -> Vec<Move> {
+        let mut moves = vec![];
+        let us = self.turn;
+        let enemy = |sq: usize| self.squares[sq].is_some_and(|(color, _)| color != us);
+        let empty = |sq: usize| self.squares[sq].is_none();
+        let quiet_or_capture = |from: usize, to: usize| Move::new(Square(from), Square(to), if enemy(to) { MoveKind::Capture } else { MoveKind::Quiet });
+
+        for from in 0..64 {
+            let Some((color, piece)) = self.squares[from] else { continue };
+            if color != us {
+                continue;
+            }
+            match piece {
+                Piece::Pawn => {
+                    let last = if us == Color::White { 7 } else { 0 };
+                    let start = if us == Color::White { 1 } else { 6 };
+                    let mut targets = vec![];
+                    if let Some(to) = offset(from, 0, forward(us)).filter(|&to| empty(to)) {
+                        targets.push((to, false));
+                        if from / 8 == start {
+                            if let Some(double) = offset(to, 0, forward(us)).filter(|&sq| empty(sq)) {
+                                moves.push(Move::new(Square(from), Square(double), MoveKind::DoublePush));
+                            }
+                        }
+                    }
+                    for df in [-1, 1] {
+                        let Some(to) = offset(from, df, forward(us)) else { continue };
+                        if enemy(to) {
+                            targets.push((to, true));
+                        } else if self.enpassant == Some(to) {
+                            moves.push(Move::new(Square(from), Square(to), MoveKind::EnPassant));
+                        }
+                    }
+                    for (to, capture) in targets {
+                        if to / 8 == last {
+                            for promo in [PromotionPiece::Queen, PromotionPiece::Rook, PromotionPiece::Bishop, PromotionPiece::Knight] {
+                                moves.push(Move::new(Square(from), Square(to), MoveKind::promotion(promo, capture)));
+                            }
+                        } else {
+                            moves.push(Move::new(Square(from), Square(to), if capture { MoveKind::Capture } else { MoveKind::Quiet }));
+                        }
+                    }
+                }
+                Piece::Knight | Piece::King => {
+                    let jumps = if piece == Piece::Knight { KNIGHT } else { KING };
+                    for (df, dr) in jumps {
+                        if let Some(to) = offset(from, df, dr).filter(|&to| empty(to) || enemy(to)) {
+                            moves.push(quiet_or_capture(from, to));
+                        }
+                    }
+                }
+                _ => {
+                    let directions: Vec<(i32, i32)> = match piece {
+                        Piece::Rook => ORTHOGONAL.to_vec(),
+                        Piece::Bishop => DIAGONAL.to_vec(),
+                        _ => ORTHOGONAL.iter().chain(DIAGONAL.iter()).copied().collect()
+                    };
+                    for (df, dr) in directions {
+                        let mut target = offset(from, df, dr);
+                        while let Some(to) = target {
+                            if empty(to) {
+                                moves.push(quiet_or_capture(from, to));
+                            } else {
+                                if enemy(to) {
+                                    moves.push(quiet_or_capture(from, to));
+                                }
+                                break;
+                            }
+                            target = offset(to, df, dr);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Castling: the king may not start on, pass or land on an attacked square
+        let (rights, king) = match us {
+            Color::White => ((self.castling[0], self.castling[1]), 4),
+            Color::Black => ((self.castling[2], self.castling[3]), 60)
+        };
+        if rights.0 && empty(king + 1) && empty(king + 2)
+            && ![king, king + 1, king + 2].iter().any(|&sq| self.attacked(sq, !us)) {
+            moves.push(Move::new(Square(king), Square(king + 2), MoveKind::KingCastle));
+        }
+        if rights.1 && empty(king - 1) && empty(king - 2) && empty(king - 3)
+            && ![king, king - 1, king - 2].iter().any(|&sq| self.attacked(sq, !us)) {
+            moves.push(Move::new(Square(king), Square(king - 2), MoveKind::QueenCastle));
+        }
+        moves
+    }
+
+    /// Returns the position after `mv`, which has to be pseudo-legal
+    pub fn make(&self, mv: &Move) -> Mailbox {
+        let mut next = *self;
+        let (from, to) = (mv.from().0, mv.to().0);
+        let moving = self.squares[from];
+        next.squares[from] = None;
+        next.squares[to] = moving;
+        match mv.kind() {
+            MoveKind::EnPassant => next.squares[to - 8 + 16 * (self.turn == Color::Black) as usize] = None,
+            MoveKind::KingCastle => next.squares.swap(to + 1, to - 1),
+            MoveKind::QueenCastle => next.squares.swap(to - 2, to + 1),
+            _ => {}
+        }
+        if let Some(promo) = mv.promotion() {
+            next.squares[to] = Some((self.turn, Piece::from(promo)));
+        }
+        next.enpassant = (mv.kind() == MoveKind::DoublePush).then(|| (from + to) / 2);
+        // Any move from or to a corner or king square removes the matching rights
+        for (i, squares) in [[4, 7], [4, 0], [60, 63], [60, 56]].iter().enumerate() {
+            if squares.contains(&from) || squares.contains(&to) {
+                next.castling[i] = false;
+            }
+        }
+        next.turn = !self.turn;
+        next
+    }
+
+    /// Returns every legal move of the side to move
+    pub fn legal_moves(&self) -> Vec<Move> {
+        self.pseudo_legal().into_iter().filter(|mv| {
+            let next = self.make(mv);
+            next.king(self.turn).is_some_and(|king| !next.attacked(king, next.turn))
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::board::Board;
+    use crate::perft::{parse_suite, RANDOM_SUITE};
+    use crate::reference::Mailbox;
+
+    fn perft(mailbox: &Mailbox, depth: usize) -> u64 {
+        match depth {
+            0 => 1,
+            1 => mailbox.legal_moves().len() as u64,
+            _ => mailbox.legal_moves().iter().map(|mv| perft(&mailbox.make(mv), depth - 1)).sum()
+        }
+    }
+
+    /// Checks the counts of the random suite up to `max_depth` against the mailbox generator,
+    /// since they were produced by the generator under test
+    fn check_random_suite(max_depth: usize) {
+        for (line, epd) in parse_suite(RANDOM_SUITE).unwrap() {
+            let mailbox = Mailbox::from_fen(&epd.board.fen()).unwrap();
+            let depths = epd.opcodes.keys().filter_map(|opcode| opcode.strip_prefix('D')?.parse().ok());
+            for depth in depths.filter(|&depth| depth <= max_depth) {
+                let expected = epd.perft(depth).unwrap();
+                assert_eq!(perft(&mailbox, depth), expected, "line {line}, depth {depth}: {}", epd.board.fen());
+            }
+        }
+    }
+
+    #[test]
+    fn test_reference_perft() {
+        let kiwipete = Mailbox::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+        assert_eq!(perft(&kiwipete, 2), 2039);
+        let position = Mailbox::from_fen("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1").unwrap();
+        assert_eq!(perft(&position, 3), 9467);
+        let fen = "8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1";
+        let enpassant = Mailbox::from_fen(fen).unwrap();
+        assert_eq!(enpassant.fen(), fen);
+        assert_eq!(perft(&enpassant, 4), crate::perft::perft(&Board::from_str(fen).unwrap(), 4));
+    }
+
+    #[test]
+    fn test_random_suite() {
+        check_random_suite(2);
+    }
+
+    #[test]
+    #[ignore = "counts every record of the random suite with the mailbox generator, use --release"]
+    fn test_random_suite_full() {
+        check_random_suite(usize::MAX);
+    }
+}
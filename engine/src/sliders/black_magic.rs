@@ -0,0 +1,28 @@
+use types::bitboard::BitBoard;
+use types::slider::Slider;
+use types::sliders::black_magic::{BISHOP_BLACK_MAGICS, BISHOP_BLACK_OFFSETS, BLACK_MAGIC_SIZE, ROOK_BLACK_MAGICS, ROOK_BLACK_OFFSETS};
+use types::sliders::common::{BISHOP_BLOCKERS, ROOK_BLOCKERS};
+use types::sliders::magic::black_magic_index;
+use types::square::Square;
+
+use crate::sliders::{include_table, SliderAttacks, Table};
+
+pub static BLACK_MAGIC_TABLE: Table<BLACK_MAGIC_SIZE> = include_table!("black_magic_table.bin", BLACK_MAGIC_SIZE);
+
+/// Fixed-shift black magics, the tables of all squares and both sliders overlap
+/// in one, see the `find_magics` tool of the `types` crate
+pub struct BlackMagic;
+
+impl SliderAttacks for BlackMagic {
+    const NAME: &'static str = "black magic";
+
+    fn bishop(sq: Square, occupied: BitBoard) -> BitBoard {
+        let index = black_magic_index(occupied, !BISHOP_BLOCKERS[sq.0], BISHOP_BLACK_MAGICS[sq.0], 64 - Slider::Bishop.shift_count());
+        BitBoard(BLACK_MAGIC_TABLE.0[index.wrapping_add(BISHOP_BLACK_OFFSETS[sq.0])])
+    }
+
+    fn rook(sq: Square, occupied: BitBoard) -> BitBoard {
+        let index = black_magic_index(occupied, !ROOK_BLOCKERS[sq.0], ROOK_BLACK_MAGICS[sq.0], 64 - Slider::Rook.shift_count());
+        BitBoard(BLACK_MAGIC_TABLE.0[index.wrapping_add(ROOK_BLACK_OFFSETS[sq.0])])
+    }
+}
@@ -0,0 +1,20 @@
+use types::bitboard::BitBoard;
+use types::sliders::dumb7fill::{bishop_moves, rook_moves};
+use types::square::Square;
+
+use crate::sliders::SliderAttacks;
+
+/// Fills every direction one step at a time, no tables at all
+pub struct Dumb7Fill;
+
+impl SliderAttacks for Dumb7Fill {
+    const NAME: &'static str = "dumb7fill";
+
+    fn bishop(sq: Square, occupied: BitBoard) -> BitBoard {
+        BitBoard(bishop_moves(1 << sq.0, occupied.0))
+    }
+
+    fn rook(sq: Square, occupied: BitBoard) -> BitBoard {
+        BitBoard(rook_moves(1 << sq.0, occupied.0))
+    }
+}
@@ -0,0 +1,32 @@
+use types::bitboard::BitBoard;
+use types::square::Square;
+
+use crate::sliders::{SliderAttacks, FIRST_RANK_ATTACKS, LINES};
+
+/// Hyperbola quintessence, `o ^ (o - 2r)` on a line and its byte-swapped mirror.
+/// Byte swapping can't reverse a rank, so ranks use a first-rank lookup instead.
+pub struct Hyperbola;
+
+fn line_attacks(sq: Square, occupied: BitBoard, mask: u64) -> u64 {
+    let bit = 1u64 << sq.0;
+    let forward = occupied.0 & mask;
+    let reverse = forward.swap_bytes();
+    let forward = forward.wrapping_sub(bit.wrapping_mul(2));
+    let reverse = reverse.wrapping_sub(bit.swap_bytes().wrapping_mul(2));
+    (forward ^ reverse.swap_bytes()) & mask
+}
+
+impl SliderAttacks for Hyperbola {
+    const NAME: &'static str = "hyperbola";
+
+    fn bishop(sq: Square, occupied: BitBoard) -> BitBoard {
+        BitBoard(line_attacks(sq, occupied, LINES.diagonal[sq.0]) | line_attacks(sq, occupied, LINES.anti_diagonal[sq.0]))
+    }
+
+    fn rook(sq: Square, occupied: BitBoard) -> BitBoard {
+        let (file, rank) = (sq.file(), sq.rank());
+        let inner = (occupied.0 >> (8 * rank + 1)) & 63;
+        let rank_attacks = (FIRST_RANK_ATTACKS[file][inner as usize] as u64) << (8 * rank);
+        BitBoard(line_attacks(sq, occupied, LINES.file[sq.0]) | rank_attacks)
+    }
+}
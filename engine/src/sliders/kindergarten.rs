@@ -0,0 +1,81 @@
+use types::bitboard::BitBoard;
+use types::sliders::dumb7fill::rook_moves;
+use types::square::Square;
+
+use crate::sliders::{first_rank_attacks, SliderAttacks, LINES};
+
+const A_FILE: u64 = 0x0101010101010101;
+const B_FILE: u64 = 0x0202020202020202;
+const C2_H7: u64 = 0x0004081020408000;
+
+/// Kindergarten bitboards, a multiplication gathers the inner occupancy of a line
+/// into six bits that index a small table shared by all squares on the same file or rank
+pub struct Kindergarten;
+
+/// First-rank attacks copied onto every rank, masked by the actual line afterwards
+static FILL_UP_ATTACKS: [[u64; 64]; 8] = {
+    let mut table = [[0; 64]; 8];
+    let mut file = 0;
+    while file < 8 {
+        let mut inner = 0;
+        while inner < 64 {
+            table[file][inner] = first_rank_attacks(file, inner) * A_FILE;
+            inner += 1;
+        }
+        file += 1;
+    }
+    table
+};
+
+/// File attacks of a slider on each rank of the a-file, indexed like `file_index`
+static A_FILE_ATTACKS: [[u64; 64]; 8] = {
+    let mut table = [[0; 64]; 8];
+    let mut rank = 0;
+    while rank < 8 {
+        let mut inner = 0;
+        while inner < 64 {
+            let mut occupied = 0;
+            let mut i = 0;
+            while i < 6 {
+                occupied |= ((inner as u64 >> i) & 1) << (8 * (i + 1));
+                i += 1;
+            }
+            table[rank][file_index(occupied)] = rook_moves(1 << (8 * rank), occupied) & A_FILE;
+            inner += 1;
+        }
+        rank += 1;
+    }
+    table
+};
+
+/// Index of a line with at most one square per file, by the occupancy of files b to g
+const fn line_index(line: u64) -> usize {
+    (line.wrapping_mul(B_FILE) >> 58) as usize
+}
+
+/// Index of the a-file occupancy, by the occupancy of ranks 2 to 7
+const fn file_index(a_file: u64) -> usize {
+    ((a_file & A_FILE).wrapping_mul(C2_H7) >> 58) as usize
+}
+
+impl SliderAttacks for Kindergarten {
+    const NAME: &'static str = "kindergarten";
+
+    fn bishop(sq: Square, occupied: BitBoard) -> BitBoard {
+        let file = sq.file();
+        let diagonal = LINES.diagonal[sq.0];
+        let anti_diagonal = LINES.anti_diagonal[sq.0];
+        BitBoard(
+            (diagonal & FILL_UP_ATTACKS[file][line_index(occupied.0 & diagonal)])
+                | (anti_diagonal & FILL_UP_ATTACKS[file][line_index(occupied.0 & anti_diagonal)])
+        )
+    }
+
+    fn rook(sq: Square, occupied: BitBoard) -> BitBoard {
+        let (file, rank) = (sq.file(), sq.rank());
+        let rank_line = LINES.rank[sq.0];
+        let files = rank_line & FILL_UP_ATTACKS[file][line_index(occupied.0 & rank_line)];
+        let ranks = A_FILE_ATTACKS[rank][file_index(occupied.0 >> file)] << file;
+        BitBoard(files | ranks)
+    }
+}
@@ -0,0 +1,26 @@
+use types::bitboard::BitBoard;
+use types::sliders::common::{ROOK_BLOCKERS, BISHOP_BLOCKERS, ROOK_MAGICS, BISHOP_MAGICS, ROOK_OFFSETS, BISHOP_OFFSETS, BISHOP_SIZE, ROOK_SIZE, BISHOP_SHIFTS, ROOK_SHIFTS};
+use types::sliders::magic::magic_index;
+use types::square::Square;
+
+use crate::sliders::{include_table, SliderAttacks, Table};
+
+pub static BISHOP_TABLE: Table<BISHOP_SIZE> = include_table!("bishop_table.bin", BISHOP_SIZE);
+pub static ROOK_TABLE: Table<ROOK_SIZE> = include_table!("rook_table.bin", ROOK_SIZE);
+
+/// Fancy magic bitboards, a multiplication hashes the relevant blockers into a table per square
+pub struct Magic;
+
+impl SliderAttacks for Magic {
+    const NAME: &'static str = "magic";
+
+    fn bishop(sq: Square, occupied: BitBoard) -> BitBoard {
+        let blockers = BitBoard(BISHOP_BLOCKERS[sq.0]) & occupied;
+        BitBoard(BISHOP_TABLE.0[magic_index(blockers, BISHOP_MAGICS[sq.0], BISHOP_SHIFTS[sq.0]) + BISHOP_OFFSETS[sq.0]])
+    }
+
+    fn rook(sq: Square, occupied: BitBoard) -> BitBoard {
+        let blockers = BitBoard(ROOK_BLOCKERS[sq.0]) & occupied;
+        BitBoard(ROOK_TABLE.0[magic_index(blockers, ROOK_MAGICS[sq.0], ROOK_SHIFTS[sq.0]) + ROOK_OFFSETS[sq.0]])
+    }
+}
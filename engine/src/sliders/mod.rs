@@ -0,0 +1,219 @@
+//! Interchangeable ways to compute bishop and rook attacks
+//!
+//! Move generation uses `Backend`, which is fancy magics unless one of the
+//! `sliders-pext`, `sliders-black-magic`, `sliders-hyperbola`, `sliders-kindergarten`
+//! or `sliders-dumb7fill` features selects another implementation. If several are enabled, the first in
+//! that order wins. Every implementation is compiled regardless, so they can be
+//! compared against each other, except `Pext` and `BlackMagic`, whose tables are only built
+//! with their features.
+
+use types::bitboard::BitBoard;
+use types::square::Square;
+
+#[cfg(feature = "sliders-black-magic")]
+pub mod black_magic;
+pub mod dumb7fill;
+pub mod hyperbola;
+pub mod kindergarten;
+pub mod magic;
+#[cfg(feature = "sliders-pext")]
+pub mod pext;
+
+#[cfg(feature = "sliders-black-magic")]
+pub use black_magic::BlackMagic;
+pub use dumb7fill::Dumb7Fill;
+pub use hyperbola::Hyperbola;
+pub use kindergarten::Kindergarten;
+pub use magic::Magic;
+#[cfg(feature = "sliders-pext")]
+pub use pext::Pext;
+
+/// Bishop and rook attacks from a square given the occupied squares, which may
+/// include the attacking piece itself
+pub trait SliderAttacks {
+    const NAME: &'static str;
+
+    fn bishop(sq: Square, occupied: BitBoard) -> BitBoard;
+
+    fn rook(sq: Square, occupied: BitBoard) -> BitBoard;
+
+    fn queen(sq: Square, occupied: BitBoard) -> BitBoard {
+        Self::bishop(sq, occupied) | Self::rook(sq, occupied)
+    }
+}
+
+#[cfg(feature = "sliders-pext")]
+pub type Backend = Pext;
+#[cfg(all(feature = "sliders-black-magic", not(feature = "sliders-pext")))]
+pub type Backend = BlackMagic;
+#[cfg(all(feature = "sliders-hyperbola", not(any(feature = "sliders-pext", feature = "sliders-black-magic"))))]
+pub type Backend = Hyperbola;
+#[cfg(all(feature = "sliders-kindergarten", not(any(feature = "sliders-pext", feature = "sliders-black-magic", feature = "sliders-hyperbola"))))]
+pub type Backend = Kindergarten;
+#[cfg(all(feature = "sliders-dumb7fill", not(any(feature = "sliders-pext", feature = "sliders-black-magic", feature = "sliders-hyperbola", feature = "sliders-kindergarten"))))]
+pub type Backend = Dumb7Fill;
+#[cfg(not(any(feature = "sliders-pext", feature = "sliders-black-magic", feature = "sliders-hyperbola", feature = "sliders-kindergarten", feature = "sliders-dumb7fill")))]
+pub type Backend = Magic;
+
+/// Attack table aligned to a cache line
+#[repr(C, align(64))]
+pub struct Table<const N: usize>(pub [u64; N]);
+
+/// Includes a table of `$len` entries written by the build script as raw bytes
+macro_rules! include_table {
+    ($file:literal, $len:expr) => {
+        // SAFETY: the byte array has the size of the u64 array, or this does not compile,
+        // and any bit pattern is a valid u64
+        $crate::sliders::Table::<{ $len }>(unsafe {
+            std::mem::transmute::<[u8; $len * 8], [u64; $len]>(*include_bytes!(concat!(env!("OUT_DIR"), "/", $file)))
+        })
+    };
+}
+pub(crate) use include_table;
+
+/// Lines through every square, excluding the square itself
+pub(crate) struct Lines {
+    pub file: [u64; Square::NUM],
+    pub rank: [u64; Square::NUM],
+    pub diagonal: [u64; Square::NUM], // a1-h8 direction
+    pub anti_diagonal: [u64; Square::NUM] // h1-a8 direction
+}
+
+pub(crate) static LINES: Lines = {
+    let mut lines = Lines { file: [0; Square::NUM], rank: [0; Square::NUM], diagonal: [0; Square::NUM], anti_diagonal: [0; Square::NUM] };
+    let mut sq = 0;
+    while sq < Square::NUM {
+        let (file, rank) = (sq % 8, sq / 8);
+        let mut other = 0;
+        while other < Square::NUM {
+            let (f, r) = (other % 8, other / 8);
+            if other != sq {
+                let bit = 1 << other;
+                if f == file {
+                    lines.file[sq] |= bit;
+                }
+                if r == rank {
+                    lines.rank[sq] |= bit;
+                }
+                if f + rank == r + file {
+                    lines.diagonal[sq] |= bit;
+                }
+                if f + r == file + rank {
+                    lines.anti_diagonal[sq] |= bit;
+                }
+            }
+            other += 1;
+        }
+        sq += 1;
+    }
+    lines
+};
+
+/// Rank attacks of a slider on `file` of the first rank, given the occupancy of files b to g
+pub(crate) const fn first_rank_attacks(file: usize, inner: usize) -> u64 {
+    types::sliders::dumb7fill::rook_moves(1 << file, (inner as u64) << 1) & 0xff
+}
+
+/// `first_rank_attacks` for every file and inner occupancy
+pub(crate) static FIRST_RANK_ATTACKS: [[u8; 64]; 8] = {
+    let mut table = [[0; 64]; 8];
+    let mut file = 0;
+    while file < 8 {
+        let mut inner = 0;
+        while inner < 64 {
+            table[file][inner] = first_rank_attacks(file, inner) as u8;
+            inner += 1;
+        }
+        file += 1;
+    }
+    table
+};
+
+#[cfg(test)]
+mod tests {
+    use types::bitboard::BitBoard;
+    use types::slider::Slider;
+    use types::sliders::magic::subsets;
+    use types::square::Square;
+
+    use crate::random::Rng;
+    use crate::sliders::{Dumb7Fill, Hyperbola, Kindergarten, Magic, SliderAttacks};
+
+    fn verify<S: SliderAttacks>() {
+        let mut rng = Rng::new(0);
+        for sq in 0..Square::NUM {
+            let sq = Square(sq);
+            for slider in [Slider::Bishop, Slider::Rook] {
+                let attacks = match slider {
+                    Slider::Bishop => S::bishop,
+                    Slider::Rook => S::rook
+                };
+                for subset in subsets(slider.blockers(sq)) {
+                    let expected = slider.pseudo_moves(sq, subset);
+                    assert_eq!(attacks(sq, subset), expected, "{} {slider:?} on {sq} with {}", S::NAME, subset.display());
+                    // Edges, the slider's own square and pieces off its lines must not matter
+                    let noise = BitBoard(rng.next_u64() & !slider.blockers(sq).0);
+                    assert_eq!(attacks(sq, subset | noise), expected, "{} {slider:?} on {sq} with {}", S::NAME, (subset | noise).display());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_magic() {
+        verify::<Magic>();
+    }
+
+    #[test]
+    #[cfg(feature = "sliders-black-magic")]
+    fn test_black_magic() {
+        verify::<crate::sliders::BlackMagic>();
+    }
+
+    #[test]
+    #[cfg(feature = "sliders-pext")]
+    fn test_pext() {
+        use types::sliders::common::{BISHOP_OFFSETS, ROOK_OFFSETS};
+        use types::sliders::magic::pext_software;
+
+        use crate::sliders::pext::{Pext, BISHOP_PEXT_TABLE, ROOK_PEXT_TABLE};
+
+        verify::<Pext>();
+        // Without BMI2 the lookups above are `Magic`'s, so the tables are checked on their own
+        for sq in 0..Square::NUM {
+            let tables = [(Slider::Bishop, &BISHOP_PEXT_TABLE.0[BISHOP_OFFSETS[sq]..]), (Slider::Rook, &ROOK_PEXT_TABLE.0[ROOK_OFFSETS[sq]..])];
+            for (slider, table) in tables {
+                let blockers = slider.blockers(Square(sq));
+                for subset in subsets(blockers) {
+                    let index = pext_software(subset.0, blockers.0) as usize;
+                    assert_eq!(BitBoard(table[index]), slider.pseudo_moves(Square(sq), subset), "{slider:?} on {} with {}", Square(sq), subset.display());
+                }
+            }
+        }
+        // And the software `pext` the tables were built with has to agree with the CPU's
+        #[cfg(target_arch = "x86_64")]
+        if Pext::available() {
+            let mut rng = Rng::new(0);
+            for _ in 0..10_000 {
+                let (value, mask) = (rng.next_u64(), rng.next_u64());
+                // SAFETY: BMI2 support was just checked
+                assert_eq!(unsafe { std::arch::x86_64::_pext_u64(value, mask) }, pext_software(value, mask));
+            }
+        }
+    }
+
+    #[test]
+    fn test_hyperbola() {
+        verify::<Hyperbola>();
+    }
+
+    #[test]
+    fn test_kindergarten() {
+        verify::<Kindergarten>();
+    }
+
+    #[test]
+    fn test_dumb7fill() {
+        verify::<Dumb7Fill>();
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use types::bitboard::BitBoard;
+use types::sliders::common::{BISHOP_BLOCKERS, BISHOP_OFFSETS, BISHOP_SIZE, ROOK_BLOCKERS, ROOK_OFFSETS, ROOK_SIZE};
+use types::square::Square;
+
+use crate::sliders::{include_table, Magic, SliderAttacks, Table};
+
+/// Indexed by `pext` of the relevant blockers, built with `pext_software` from `types`
+pub static BISHOP_PEXT_TABLE: Table<BISHOP_SIZE> = include_table!("bishop_pext_table.bin", BISHOP_SIZE);
+pub static ROOK_PEXT_TABLE: Table<ROOK_SIZE> = include_table!("rook_pext_table.bin", ROOK_SIZE);
+
+/// Bit extraction with BMI2 `pext`, which packs the relevant blockers into a dense
+/// index. The CPU is checked once at first use and `Magic` is used without BMI2.
+/// Note that `pext` is microcoded and slow on AMD processors before Zen 3.
+pub struct Pext;
+
+/// Only ever holds `Magic`'s lookups or, once BMI2 was detected, `bishop_bmi2`/`rook_bmi2`
+type Lookup = unsafe fn(Square, BitBoard) -> BitBoard;
+
+// Start out on `detect_*`, which swap in the lookups for the CPU, so later calls go straight there
+static BISHOP: AtomicPtr<()> = AtomicPtr::new(detect_bishop as *mut ());
+static ROOK: AtomicPtr<()> = AtomicPtr::new(detect_rook as *mut ());
+
+/// # Safety
+/// The CPU must support BMI2
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "bmi2")]
+unsafe fn bishop_bmi2(sq: Square, occupied: BitBoard) -> BitBoard {
+    let index = std::arch::x86_64::_pext_u64(occupied.0, BISHOP_BLOCKERS[sq.0]) as usize;
+    BitBoard(BISHOP_PEXT_TABLE.0[BISHOP_OFFSETS[sq.0] + index])
+}
+
+/// # Safety
+/// The CPU must support BMI2
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "bmi2")]
+unsafe fn rook_bmi2(sq: Square, occupied: BitBoard) -> BitBoard {
+    let index = std::arch::x86_64::_pext_u64(occupied.0, ROOK_BLOCKERS[sq.0]) as usize;
+    BitBoard(ROOK_PEXT_TABLE.0[ROOK_OFFSETS[sq.0] + index])
+}
+
+fn has_bmi2() -> bool {
+    #[cfg(target_arch = "x86_64")]
+    return std::is_x86_feature_detected!("bmi2");
+    #[cfg(not(target_arch = "x86_64"))]
+    return false;
+}
+
+/// Picks the bishop and rook lookups for this CPU and stores them for later calls
+fn resolve() -> (Lookup, Lookup) {
+    #[cfg(target_arch = "x86_64")]
+    let lookups: (Lookup, Lookup) = match has_bmi2() {
+        true => (bishop_bmi2, rook_bmi2),
+        false => (Magic::bishop, Magic::rook)
+    };
+    #[cfg(not(target_arch = "x86_64"))]
+    let lookups: (Lookup, Lookup) = (Magic::bishop, Magic::rook);
+    BISHOP.store(lookups.0 as *mut (), Ordering::Relaxed);
+    ROOK.store(lookups.1 as *mut (), Ordering::Relaxed);
+    lookups
+}
+
+fn detect_bishop(sq: Square, occupied: BitBoard) -> BitBoard {
+    // SAFETY: `resolve` only returns the BMI2 lookups on CPUs with BMI2
+    unsafe { resolve().0(sq, occupied) }
+}
+
+fn detect_rook(sq: Square, occupied: BitBoard) -> BitBoard {
+    // SAFETY: `resolve` only returns the BMI2 lookups on CPUs with BMI2
+    unsafe { resolve().1(sq, occupied) }
+}
+
+impl Pext {
+    /// Specifies whether the CPU supports BMI2, otherwise lookups fall back to `Magic`
+    pub fn available() -> bool {
+        has_bmi2()
+    }
+}
+
+impl SliderAttacks for Pext {
+    const NAME: &'static str = "pext";
+
+    fn bishop(sq: Square, occupied: BitBoard) -> BitBoard {
+        // SAFETY: `BISHOP` only holds a `Lookup`, and only holds the BMI2 one on CPUs with BMI2
+        unsafe {
+            let lookup = std::mem::transmute::<*mut (), Lookup>(BISHOP.load(Ordering::Relaxed));
+            lookup(sq, occupied)
+        }
+    }
+
+    fn rook(sq: Square, occupied: BitBoard) -> BitBoard {
+        // SAFETY: `ROOK` only holds a `Lookup`, and only holds the BMI2 one on CPUs with BMI2
+        unsafe {
+            let lookup = std::mem::transmute::<*mut (), Lookup>(ROOK.load(Ordering::Relaxed));
+            lookup(sq, occupied)
+        }
+    }
+}
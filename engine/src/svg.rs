@@ -0,0 +1,227 @@
+use std::fmt::Write;
+
+use types::bitboard::BitBoard;
+use types::color::Color;
+use types::piece::Piece;
+use types::square::Square;
+
+use crate::board::{Board, PIECES};
+
+/// Side of a square in SVG units
+const SQUARE: usize = 45;
+/// Space around the board reserved for coordinates
+const MARGIN: usize = 20;
+
+const LIGHT_SQUARE: &str = "#f0d9b5";
+const DARK_SQUARE: &str = "#b58863";
+const HIGHLIGHT: &str = "#9bc700";
+const ARROW: &str = "#15781b";
+
+/// Piece shapes drawn in a 45x45 square; fill and stroke are set per use
+fn piece_shape(piece: Piece) -> &'static str {
+    match piece {
+        Piece::King => concat!(
+            r#"<path d="M11,32 c-3,-8 1,-14 7,-12 l4.5,3 l4.5,-3 c6,-2 10,4 7,12 z"/>"#,
+            r#"<rect x="11" y="33" width="23" height="5"/>"#,
+            r#"<path d="M22.5,6 v14 M18,10 h9" fill="none" stroke-width="2"/>"#,
+        ),
+        Piece::Queen => concat!(
+            r#"<path d="M13,32 L9,16 L15.5,25 L15,12 L19,24 L22.5,10 L26,24 L30,12 L29.5,25 L36,16 L32,32 z"/>"#,
+            r#"<rect x="12" y="33" width="21" height="5"/>"#,
+            r#"<circle cx="9" cy="16" r="2"/><circle cx="15" cy="12" r="2"/><circle cx="22.5" cy="10" r="2"/>"#,
+            r#"<circle cx="30" cy="12" r="2"/><circle cx="36" cy="16" r="2"/>"#,
+        ),
+        Piece::Rook => r#"<path d="M11,9 h5 v4 h4 v-4 h5 v4 h4 v-4 h5 v8 l-3,3 v11 l3,3 v4 h-23 v-4 l3,-3 v-11 l-3,-3 z"/>"#,
+        Piece::Bishop => concat!(
+            r#"<circle cx="22.5" cy="6" r="2.5"/>"#,
+            r#"<path d="M22.5,8 c5,4 9,10 9,15 c0,5 -4,7 -9,7 c-5,0 -9,-2 -9,-7 c0,-5 4,-11 9,-15 z"/>"#,
+            r#"<rect x="18" y="30" width="9" height="3"/>"#,
+            r#"<path d="M15,33 h15 l2,5 h-19 z"/>"#,
+        ),
+        Piece::Knight => r#"<path d="M14,38 h20 c0,-10 -1,-22 -9,-28 l-2,-3 l-2,4 c-3,1 -8,6 -10,12 c0,2 1,3 3,3 c2,-1 4,-3 7,-3 c-4,6 -7,9 -7,15 z"/>"#,
+        Piece::Pawn => concat!(
+            r#"<circle cx="22.5" cy="14" r="5.5"/>"#,
+            r#"<path d="M17,21 h11 l3,12 h-17 z"/>"#,
+            r#"<rect x="11" y="33" width="23" height="5"/>"#,
+        ),
+    }
+}
+
+fn piece_name(piece: Piece) -> &'static str {
+    match piece {
+        Piece::King => "king",
+        Piece::Queen => "queen",
+        Piece::Rook => "rook",
+        Piece::Bishop => "bishop",
+        Piece::Knight => "knight",
+        Piece::Pawn => "pawn"
+    }
+}
+
+/// Self-contained SVG diagram of a board, created with `Board::svg`
+#[derive(Clone, Debug)]
+pub struct BoardSvg<'a> {
+    board: &'a Board,
+    coordinates: bool,
+    flipped: bool,
+    highlight: BitBoard,
+    arrows: Vec<(Square, Square)>,
+}
+
+impl Board {
+    /// Returns an SVG diagram of the position that can be customised before rendering
+    pub fn svg(&self) -> BoardSvg<'_> {
+        BoardSvg {
+            board: self,
+            coordinates: true,
+            flipped: false,
+            highlight: BitBoard::EMPTY,
+            arrows: vec![],
+        }
+    }
+}
+
+impl BoardSvg<'_> {
+    /// Draws file letters and rank numbers around the board
+    pub fn coordinates(mut self, coordinates: bool) -> Self {
+        self.coordinates = coordinates;
+        self
+    }
+
+    /// Draws the board from Black's side
+    pub fn flipped(mut self, flipped: bool) -> Self {
+        self.flipped = flipped;
+        self
+    }
+
+    /// Tints every square of a bitboard
+    pub fn highlight(mut self, squares: BitBoard) -> Self {
+        self.highlight = squares;
+        self
+    }
+
+    /// Draws an arrow between the centres of two squares
+    pub fn arrow(mut self, from: Square, to: Square) -> Self {
+        self.arrows.push((from, to));
+        self
+    }
+
+    fn margin(&self) -> usize {
+        if self.coordinates { MARGIN } else { 0 }
+    }
+
+    /// Returns the top left corner of a square
+    fn position(&self, sq: Square) -> (usize, usize) {
+        let (file, rank) = (sq.0 % 8, sq.0 / 8);
+        let (col, row) = match self.flipped {
+            true => (7 - file, rank),
+            false => (file, 7 - rank)
+        };
+        (self.margin() + col * SQUARE, self.margin() + row * SQUARE)
+    }
+
+    fn center(&self, sq: Square) -> (f32, f32) {
+        let (x, y) = self.position(sq);
+        (x as f32 + SQUARE as f32 / 2.0, y as f32 + SQUARE as f32 / 2.0)
+    }
+
+    /// Renders the diagram
+    pub fn render(&self) -> String {
+        let size = 8 * SQUARE + 2 * self.margin();
+        let mut svg = String::new();
+        let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" version="1.1" viewBox="0 0 {size} {size}" width="{size}" height="{size}">"#);
+
+        svg.push_str("<defs>\n");
+        for piece in PIECES {
+            let _ = writeln!(svg, r#"<g id="{}" stroke-width="1.5" stroke-linejoin="round">{}</g>"#, piece_name(piece), piece_shape(piece));
+        }
+        let _ = writeln!(svg, r#"<marker id="arrowhead" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="2.5" markerHeight="2.5" orient="auto"><path d="M0,0 L10,5 L0,10 z" fill="{ARROW}"/></marker>"#);
+        svg.push_str("</defs>\n");
+
+        if self.coordinates {
+            let _ = writeln!(svg, r##"<rect x="0" y="0" width="{size}" height="{size}" fill="#212121"/>"##);
+        }
+        for sq in (0..64).map(Square) {
+            let (x, y) = self.position(sq);
+            let color = if (sq.0 / 8 + sq.0 % 8).is_multiple_of(2) { DARK_SQUARE } else { LIGHT_SQUARE };
+            let _ = writeln!(svg, r#"<rect x="{x}" y="{y}" width="{SQUARE}" height="{SQUARE}" fill="{color}"/>"#);
+        }
+        for sq in self.highlight {
+            let (x, y) = self.position(sq);
+            let _ = writeln!(svg, r#"<rect x="{x}" y="{y}" width="{SQUARE}" height="{SQUARE}" fill="{HIGHLIGHT}" fill-opacity="0.5"/>"#);
+        }
+
+        if self.coordinates {
+            for i in 0..8 {
+                let file = Square(i).to_string().chars().next().unwrap_or('a');
+                let (x, _) = self.position(Square(i));
+                let (_, y) = self.position(Square(8 * i));
+                let (x, y) = (x + SQUARE / 2, y + SQUARE / 2);
+                let bottom = size - MARGIN / 2;
+                let _ = writeln!(svg, r##"<text x="{x}" y="{bottom}" fill="#e5e5e5" font-family="sans-serif" font-size="12" text-anchor="middle" dominant-baseline="central">{file}</text>"##);
+                let _ = writeln!(svg, r##"<text x="{}" y="{y}" fill="#e5e5e5" font-family="sans-serif" font-size="12" text-anchor="middle" dominant-baseline="central">{}</text>"##, MARGIN / 2, i + 1);
+            }
+        }
+
+        for sq in self.board.occupied() {
+            let Some(piece) = self.board.squares[sq.0] else { continue };
+            let (x, y) = self.position(sq);
+            let (fill, stroke) = match (sq.bitboard() & self.board.all[Color::White as usize]).is_empty() {
+                true => ("#000", "#000"),
+                false => ("#fff", "#000")
+            };
+            let _ = writeln!(svg, r##"<use xlink:href="#{}" href="#{}" x="{x}" y="{y}" fill="{fill}" stroke="{stroke}"/>"##, piece_name(piece), piece_name(piece));
+        }
+
+        for &(from, to) in &self.arrows {
+            let ((x1, y1), (x2, y2)) = (self.center(from), self.center(to));
+            let _ = writeln!(svg, r#"<line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}" stroke="{ARROW}" stroke-width="9" stroke-opacity="0.8" marker-end="url(#arrowhead)"/>"#);
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+impl std::fmt::Display for BoardSvg<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use types::square::Square;
+
+    use crate::board::{Board, STARTPOS};
+
+    /// Compares against a stored snapshot; set `UPDATE_SNAPSHOTS` to rewrite it
+    fn assert_snapshot(name: &str, svg: &str) {
+        let path = format!("{}/tests/snapshots/{name}.svg", env!("CARGO_MANIFEST_DIR"));
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(&path, svg).unwrap();
+        }
+        let expected = std::fs::read_to_string(&path).unwrap_or_default();
+        assert_eq!(svg, expected, "SVG snapshot {name} differs");
+    }
+
+    #[test]
+    fn test_svg_snapshots() {
+        let board = Board::from_str(STARTPOS).unwrap();
+        assert_snapshot("startpos", &board.svg().render());
+
+        let board = Board::from_str("r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4").unwrap();
+        let sq = |s| Square::from_str(s).unwrap();
+        let svg = board.svg()
+            .flipped(true)
+            .coordinates(false)
+            .highlight(sq("h5").bitboard() | sq("f7").bitboard())
+            .arrow(sq("h5"), sq("f7"))
+            .render();
+        assert_snapshot("scholars_mate", &svg);
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<use ").count(), 32);
+    }
+}
@@ -0,0 +1,159 @@
+use types::bitboard::BitBoard;
+use types::color::Color;
+use types::nonsliders::common::PAWN_ATTACKS;
+
+use crate::board::Board;
+
+/// Random keys for every piece on every square, castling right, en-passant file and side to move
+pub struct ZobristKeys {
+    pub pieces: [[[u64; 64]; 6]; 2],
+    pub castling: [u64; 4],
+    pub enpassant: [u64; 8],
+    pub black: u64,
+}
+
+const fn splitmix(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    (state, z ^ (z >> 31))
+}
+
+const fn generate_keys() -> ZobristKeys {
+    let mut keys = ZobristKeys { pieces: [[[0; 64]; 6]; 2], castling: [0; 4], enpassant: [0; 8], black: 0 };
+    let mut state = 0x5eed;
+    let mut key;
+    let mut color = 0;
+    while color < 2 {
+        let mut piece = 0;
+        while piece < 6 {
+            let mut sq = 0;
+            while sq < 64 {
+                (state, key) = splitmix(state);
+                keys.pieces[color][piece][sq] = key;
+                sq += 1;
+            }
+            piece += 1;
+        }
+        color += 1;
+    }
+    let mut i = 0;
+    while i < 4 {
+        (state, key) = splitmix(state);
+        keys.castling[i] = key;
+        i += 1;
+    }
+    i = 0;
+    while i < 8 {
+        (state, key) = splitmix(state);
+        keys.enpassant[i] = key;
+        i += 1;
+    }
+    (_, keys.black) = splitmix(state);
+    keys
+}
+
+pub static ZOBRIST: ZobristKeys = generate_keys();
+
+impl Board {
+    /// Returns the Zobrist key of the position
+    ///
+    /// The key is computed when a FEN is parsed and kept up to date by
+    /// `apply_move` and `undo_move`.
+    pub fn hash(&self) -> u64 {
+        self.key
+    }
+
+    /// Computes the Zobrist key of the position from scratch
+    pub fn compute_hash(&self) -> u64 {
+        let mut hash = 0;
+        for color in 0..2 {
+            for piece in 0..6 {
+                for sq in self.pieces[color][piece] {
+                    hash ^= ZOBRIST.pieces[color][piece][sq.0];
+                }
+            }
+        }
+        hash ^= self.castling_key() ^ self.enpassant_key();
+        if self.turn == Color::Black {
+            hash ^= ZOBRIST.black;
+        }
+        hash
+    }
+
+    /// Returns the combined key of the castling rights
+    pub(crate) fn castling_key(&self) -> u64 {
+        let rights = [self.castling[0].0, self.castling[0].1, self.castling[1].0, self.castling[1].1];
+        rights.into_iter().zip(ZOBRIST.castling).filter(|(allowed, _)| *allowed).fold(0, |hash, (_, key)| hash ^ key)
+    }
+
+    /// Returns the key of the en-passant file, if a pawn of the side to move could capture there
+    ///
+    /// Positions reached by a double push then share their key with the same
+    /// position set up from a FEN without an en-passant square.
+    pub(crate) fn enpassant_key(&self) -> u64 {
+        let mut hash = 0;
+        for sq in self.enpassant {
+            let capturers = BitBoard(PAWN_ATTACKS[1 - self.turn as usize][sq.0]) & self.pieces[self.turn as usize][5];
+            if !capturers.is_empty() {
+                hash ^= ZOBRIST.enpassant[sq.0 % 8];
+            }
+        }
+        hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::board::{Board, STARTPOS};
+    use crate::random::Rng;
+
+    #[test]
+    fn test_zobrist_hash() {
+        let start = Board::from_str(STARTPOS).unwrap();
+        let play = |moves: &[&str]| {
+            let mut board = start;
+            for uci in moves {
+                let mv = board.parse_uci(uci).unwrap();
+                board.apply_move(&mv);
+            }
+            board
+        };
+        // Transpositions share a key, the side to move does not
+        assert_eq!(play(&["g1f3", "g8f6", "b1c3"]).hash(), play(&["b1c3", "g8f6", "g1f3"]).hash());
+        assert_ne!(play(&["g1f3", "g8f6", "f3g1", "f6g8"]).hash(), play(&["g1f3", "g8f6", "f3g1"]).hash());
+        assert_eq!(play(&["g1f3", "g8f6", "f3g1", "f6g8"]).hash(), start.hash());
+        assert_ne!(start.hash(), Board::from_str("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w Kkq - 0 1").unwrap().hash());
+        // An en-passant square only counts if a pawn can capture on it
+        let fen = |s| Board::from_str(s).unwrap().hash();
+        assert_eq!(play(&["e2e4"]).hash(), fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"));
+        assert_eq!(play(&["e2e4"]).hash(), fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"));
+        let capturable = play(&["e2e4", "a7a6", "e4e5", "d7d5"]);
+        assert_eq!(capturable.hash(), fen("rnbqkbnr/1pp1pppp/p7/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3"));
+        assert_ne!(capturable.hash(), fen("rnbqkbnr/1pp1pppp/p7/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq - 0 3"));
+    }
+
+    #[test]
+    fn test_incremental_hash() {
+        let fens = [STARTPOS, "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1"];
+        for fen in fens {
+            let mut rng = Rng::new(3);
+            for _ in 0..50 {
+                let mut board = Board::from_str(fen).unwrap();
+                for _ in 0..40 {
+                    let moves = board.moves();
+                    let Some(mv) = rng.choose(&moves) else { break };
+                    let before = board.hash();
+                    board.apply_move(mv);
+                    assert_eq!(board.hash(), board.compute_hash(), "{} {mv}", board.fen());
+                    let mut undone = board;
+                    undone.undo_move(mv);
+                    assert_eq!(undone.hash(), before);
+                }
+            }
+        }
+    }
+}
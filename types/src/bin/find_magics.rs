@@ -0,0 +1,154 @@
+//! Searches slider magics and rewrites the constants they are stored in
+//!
+//! `fancy` finds a magic per square using the fewest index bits possible and rewrites
+//! the magics, shifts and offsets in `src/sliders/common.rs`. `black` finds fixed-shift
+//! black magics, packs the tables of all squares into one overlapping table and
+//! writes `src/sliders/black_magic.rs`. Both verify every blocker subset against
+//! dumb7fill before writing anything.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use types::slider::Slider;
+use types::sliders::magic::{black_magic_index, magic_index};
+use types::sliders::search::{black_magics, fancy_magic, fit_black_magic, occupancies, PackedTable, Rng};
+use types::square::Square;
+
+const USAGE: &str = "Usage:
+  find_magics fancy [seed]               rewrites the magics, shifts and offsets in src/sliders/common.rs
+  find_magics black [seed] [seconds]     writes src/sliders/black_magic.rs, searching each rook
+                                         square for `seconds` (1 by default) and each bishop square
+                                         for a tenth of that, then as long again for squares that
+                                         leave unused entries in the packed table";
+
+fn hex<T: std::fmt::LowerHex>(values: &[T]) -> String {
+    values.iter().map(|value| format!("{value:#x}")).collect::<Vec<_>>().join(", ")
+}
+
+fn fancy(seed: u64) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/sliders/common.rs");
+    let mut source = fs::read_to_string(&path).expect("Could not read common.rs");
+    let mut rng = Rng::new(seed);
+    for (slider, name) in [(Slider::Bishop, "BISHOP"), (Slider::Rook, "ROOK")] {
+        let mut magics = vec![];
+        let mut shifts = vec![];
+        let mut offsets = vec![];
+        let mut size = 0;
+        for sq in 0..Square::NUM {
+            let (magic, shift) = fancy_magic(slider, Square(sq), &mut rng);
+            magics.push(magic);
+            shifts.push(shift);
+            offsets.push(size);
+            size += 1 << (64 - shift);
+        }
+
+        let mut table = vec![0; size];
+        for sq in 0..Square::NUM {
+            for (subset, attacks) in occupancies(slider, Square(sq)) {
+                table[magic_index(subset, magics[sq], shifts[sq]) + offsets[sq]] = attacks.0;
+            }
+        }
+        for sq in 0..Square::NUM {
+            for (subset, attacks) in occupancies(slider, Square(sq)) {
+                assert_eq!(table[magic_index(subset, magics[sq], shifts[sq]) + offsets[sq]], attacks.0, "{name} magic on {}", Square(sq));
+            }
+        }
+
+        let shifts = shifts.iter().map(usize::to_string).collect::<Vec<_>>().join(", ");
+        let lines = [
+            (format!("pub const {name}_SHIFTS:"), format!("pub const {name}_SHIFTS: [usize; Square::NUM] = [{shifts}];")),
+            (format!("pub const {name}_MAGICS:"), format!("pub const {name}_MAGICS: [u64; Square::NUM] = [{}];", hex(&magics))),
+            (format!("pub const {name}_OFFSETS:"), format!("pub const {name}_OFFSETS: [usize; Square::NUM] = [{}];", hex(&offsets))),
+            (format!("pub const {name}_SIZE:"), format!("pub const {name}_SIZE: usize = {size:#x};"))
+        ];
+        source = source.lines().map(|line| {
+            lines.iter().find(|(prefix, _)| line.starts_with(prefix.as_str())).map_or(line, |(_, replacement)| replacement.as_str())
+        }).collect::<Vec<_>>().join("\n") + "\n";
+        println!("{name}: {size} entries, {} KiB", size * 8 / 1024);
+    }
+    fs::write(&path, source).expect("Could not write common.rs");
+}
+
+fn black(seed: u64, seconds: f64) {
+    const KEEP: usize = 16;
+    let mut rng = Rng::new(seed);
+    let sliders = [Slider::Bishop, Slider::Rook];
+    let budget = |slider| match slider {
+        Slider::Bishop => Duration::from_secs_f64(seconds / 10.0),
+        Slider::Rook => Duration::from_secs_f64(seconds)
+    };
+    let start = Instant::now();
+    let mut candidates = vec![];
+    for (i, slider) in sliders.into_iter().enumerate() {
+        for sq in 0..Square::NUM {
+            let found = black_magics(slider, Square(sq), &mut rng, budget(slider), KEEP);
+            eprintln!("{slider:?} {}: {} magics spanning {} to {} entries ({:.0?})",
+                Square(sq), found.len(), found[0].1.span(), found[found.len() - 1].1.span(), start.elapsed());
+            candidates.push((64 * i + sq, found));
+        }
+    }
+
+    // Widest segments first, the narrow ones then fill their holes. Of the magics found
+    // for a square, the one that grows the table least is used, and when that still
+    // leaves unused entries the search goes on for one that fits better.
+    candidates.sort_by_key(|(_, found)| std::cmp::Reverse(found[0].1.span()));
+    let mut magics = vec![0; 2 * Square::NUM];
+    let mut offsets = vec![0; 2 * Square::NUM];
+    let mut table = PackedTable::default();
+    for (i, found) in candidates {
+        let (slider, sq) = (sliders[i / 64], Square(i % 64));
+        let (mut magic, mut segment, mut position) = fit_black_magic(slider, sq, &mut rng, Duration::ZERO, &table, &found);
+        let growth = table.growth(&segment, position);
+        if growth > segment.entries.len() {
+            (magic, segment, position) = fit_black_magic(slider, sq, &mut rng, budget(slider), &table, &found);
+            eprintln!("{slider:?} {sq}: table grows by {} instead of {growth} entries ({:.0?})",
+                table.growth(&segment, position), start.elapsed());
+        }
+        magics[i] = magic;
+        offsets[i] = table.place(&segment, position);
+    }
+    let table = table.into_table();
+
+    for (i, slider) in sliders.into_iter().enumerate() {
+        for sq in 0..Square::NUM {
+            let not_mask = !slider.blockers(Square(sq)).0;
+            let (magic, offset) = (magics[64 * i + sq], offsets[64 * i + sq]);
+            for (subset, attacks) in occupancies(slider, Square(sq)) {
+                let index = black_magic_index(subset, not_mask, magic, 64 - slider.shift_count());
+                assert_eq!(table[index.wrapping_add(offset)], attacks.0, "{slider:?} black magic on {}", Square(sq));
+            }
+        }
+    }
+
+    let source = format!(
+        "//! Generated by `cargo run --release -p types --bin find_magics -- black {seed} {seconds}`
+
+use crate::square::Square;
+
+/// Fixed-shift magics, every bishop index takes 9 bits and every rook index 12
+pub const BISHOP_BLACK_MAGICS: [u64; Square::NUM] = [{}];
+pub const ROOK_BLACK_MAGICS: [u64; Square::NUM] = [{}];
+
+/// Added to the indices with wrapping arithmetic, the tables of all squares overlap in one
+pub const BISHOP_BLACK_OFFSETS: [usize; Square::NUM] = [{}];
+pub const ROOK_BLACK_OFFSETS: [usize; Square::NUM] = [{}];
+pub const BLACK_MAGIC_SIZE: usize = {:#x};
+",
+        hex(&magics[..64]), hex(&magics[64..]), hex(&offsets[..64]), hex(&offsets[64..]), table.len()
+    );
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/sliders/black_magic.rs");
+    fs::write(path, source).expect("Could not write black_magic.rs");
+    println!("Bishops and rooks: {} entries, {} KiB", table.len(), table.len() * 8 / 1024);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let seed = args.get(1).map_or(0, |arg| arg.parse().expect(USAGE));
+    match args.first().map(String::as_str) {
+        Some("fancy") => fancy(seed),
+        Some("black") => black(seed, args.get(2).map_or(1.0, |arg| arg.parse().expect(USAGE))),
+        _ => panic!("{USAGE}")
+    }
+}
@@ -90,7 +90,7 @@ impl std::fmt::Display for BitBoard {
         let mask = BitBoard(0xff << 56); // Eighth rank
         let mut str = String::new();
         for i in 0..8 {
-            let rank = (self.0 & mask.shr(8 * i).0) >> 8 * (7 - i);
+            let rank = (self.0 & mask.shr(8 * i).0) >> (8 * (7 - i));
             let rank_str = format!("{rank:0>8b}\n").chars().rev().collect::<String>().replace("0", ".").replace("1", "x");
             str += rank_str.as_str();
         }
@@ -1,8 +1,73 @@
+use thiserror::Error;
+
 use crate::{piece::{Piece, PromotionPiece}, square::Square, bitboard::BitBoard, color::Color};
 
-#[derive(Clone, Copy)]
-pub enum MoveFlags {
-    Promotion(PromotionPiece)
+/// What a move does besides relocating a piece, stored in the upper 4 bits of a `Move`
+///
+/// Promotions set bit 3 and promotion captures additionally set bit 2, the low
+/// two bits then hold the `PromotionPiece`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MoveKind {
+    Quiet = 0,
+    DoublePush = 1,
+    KingCastle = 2,
+    QueenCastle = 3,
+    Capture = 4,
+    EnPassant = 5,
+    QueenPromotion = 8,
+    RookPromotion = 9,
+    BishopPromotion = 10,
+    KnightPromotion = 11,
+    QueenPromotionCapture = 12,
+    RookPromotionCapture = 13,
+    BishopPromotionCapture = 14,
+    KnightPromotionCapture = 15,
+}
+
+impl MoveKind {
+    const ALL: [Option<MoveKind>; 16] = [
+        Some(Self::Quiet), Some(Self::DoublePush), Some(Self::KingCastle), Some(Self::QueenCastle),
+        Some(Self::Capture), Some(Self::EnPassant), None, None,
+        Some(Self::QueenPromotion), Some(Self::RookPromotion), Some(Self::BishopPromotion), Some(Self::KnightPromotion),
+        Some(Self::QueenPromotionCapture), Some(Self::RookPromotionCapture), Some(Self::BishopPromotionCapture), Some(Self::KnightPromotionCapture),
+    ];
+
+    /// Decodes the 4-bit kind, returning `None` for the two unused values
+    pub const fn from_bits(bits: u16) -> Option<MoveKind> {
+        Self::ALL[(bits & 0xf) as usize]
+    }
+
+    /// Returns the promotion kind for `promotion`, optionally capturing
+    pub const fn promotion(promotion: PromotionPiece, capture: bool) -> MoveKind {
+        match Self::from_bits(8 | ((capture as u16) << 2) | promotion as u16) {
+            Some(kind) => kind,
+            None => unreachable!()
+        }
+    }
+
+    pub const fn is_capture(&self) -> bool {
+        matches!(self, Self::Capture | Self::EnPassant) || *self as u16 >= 12
+    }
+
+    pub const fn is_promotion(&self) -> bool {
+        *self as u16 >= 8
+    }
+
+    pub const fn is_castle(&self) -> bool {
+        matches!(self, Self::KingCastle | Self::QueenCastle)
+    }
+
+    pub const fn promotion_piece(&self) -> Option<PromotionPiece> {
+        if !self.is_promotion() {
+            return None;
+        }
+        Some(match *self as u16 & 3 {
+            0 => PromotionPiece::Queen,
+            1 => PromotionPiece::Rook,
+            2 => PromotionPiece::Bishop,
+            _ => PromotionPiece::Knight
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -27,77 +92,384 @@ impl PieceMoves {
         self.moves.is_empty()
     }
 
-    /// Converts `PieceMoves` to `Vec<Move>`
-    pub fn convert(&self, color: Color) -> Vec<Move> {
+    /// Converts `PieceMoves` to a `MoveList`
+    ///
+    /// `them` holds the enemy pieces and `enpassant` the en-passant target square,
+    /// both are needed to classify each move.
+    pub fn convert(&self, color: Color, them: BitBoard, enpassant: BitBoard) -> MoveList {
+        let mut moves = MoveList::new();
+        self.extend(&mut moves, color, them, enpassant);
+        moves
+    }
+
+    /// Appends every move of this group to `moves`, expanding promotions
+    pub fn extend(&self, moves: &mut MoveList, color: Color, them: BitBoard, enpassant: BitBoard) {
         let eighth_rank = match color {
             Color::White => BitBoard(0xff00000000000000),
             Color::Black => BitBoard(0xff)
         };
-        let mut moves = vec![];
-        if self.piece == Piece::Pawn {
-            for sq in self.moves & !eighth_rank {
-                moves.push(Move {
-                    piece: self.piece,
-                    from: self.from,
-                    to: sq,
-                    flags: None
-                })
+        match self.piece {
+            Piece::Pawn => {
+                for to in self.moves & !eighth_rank {
+                    let kind = if !(to.bitboard() & them).is_empty() {
+                        MoveKind::Capture
+                    } else if !(to.bitboard() & enpassant).is_empty() {
+                        MoveKind::EnPassant
+                    } else if to.0.abs_diff(self.from.0) == 16 {
+                        MoveKind::DoublePush
+                    } else {
+                        MoveKind::Quiet
+                    };
+                    moves.push(Move::new(self.from, to, kind));
+                }
+                for to in self.moves & eighth_rank {
+                    let capture = !(to.bitboard() & them).is_empty();
+                    for promotion in [PromotionPiece::Queen, PromotionPiece::Rook, PromotionPiece::Bishop, PromotionPiece::Knight] {
+                        moves.push(Move::new(self.from, to, MoveKind::promotion(promotion, capture)));
+                    }
+                }
             }
-            for sq in self.moves & eighth_rank {
-                moves.push(Move {
-                    piece: self.piece,
-                    from: self.from,
-                    to: sq,
-                    flags: Some(MoveFlags::Promotion(PromotionPiece::Queen))
-                });
-                moves.push(Move {
-                    piece: self.piece,
-                    from: self.from,
-                    to: sq,
-                    flags: Some(MoveFlags::Promotion(PromotionPiece::Rook))
-                });
-                moves.push(Move {
-                    piece: self.piece,
-                    from: self.from,
-                    to: sq,
-                    flags: Some(MoveFlags::Promotion(PromotionPiece::Bishop))
-                });
-                moves.push(Move {
-                    piece: self.piece,
-                    from: self.from,
-                    to: sq,
-                    flags: Some(MoveFlags::Promotion(PromotionPiece::Knight))
-                });
+            Piece::King => {
+                for to in self.moves {
+                    let kind = if !(to.bitboard() & them).is_empty() {
+                        MoveKind::Capture
+                    } else if to.0 == self.from.0 + 2 {
+                        MoveKind::KingCastle
+                    } else if to.0 + 2 == self.from.0 {
+                        MoveKind::QueenCastle
+                    } else {
+                        MoveKind::Quiet
+                    };
+                    moves.push(Move::new(self.from, to, kind));
+                }
             }
-        } else {
-            for sq in self.moves {
-                moves.push(Move {
-                    piece: self.piece,
-                    from: self.from,
-                    to: sq,
-                    flags: None
-                });
+            _ => {
+                for to in self.moves & them {
+                    moves.push(Move::new(self.from, to, MoveKind::Capture));
+                }
+                for to in self.moves & !them {
+                    moves.push(Move::new(self.from, to, MoveKind::Quiet));
+                }
             }
         }
-        moves
     }
 }
 
-#[derive(Clone, Copy)]
-pub struct Move {
-    pub piece: Piece,
-    pub from: Square,
-    pub to: Square,
-    pub flags: Option<MoveFlags>
+#[derive(Debug, Error)]
+#[error("{0:#06x} is not a valid move encoding")]
+pub struct MoveDecodeError(pub u16);
+
+/// A move packed into 16 bits: origin in bits 0-5, destination in bits 6-11
+/// and the `MoveKind` in bits 12-15
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Move(u16);
+
+impl Move {
+    /// Encodes `a1a1`, which is never a legal move
+    pub const NULL: Move = Move(0);
+
+    pub const fn new(from: Square, to: Square, kind: MoveKind) -> Self {
+        Move(from.0 as u16 | ((to.0 as u16) << 6) | ((kind as u16) << 12))
+    }
+
+    pub const fn from(&self) -> Square {
+        Square((self.0 & 0x3f) as usize)
+    }
+
+    pub const fn to(&self) -> Square {
+        Square(((self.0 >> 6) & 0x3f) as usize)
+    }
+
+    pub const fn kind(&self) -> MoveKind {
+        match MoveKind::from_bits(self.0 >> 12) {
+            Some(kind) => kind,
+            None => MoveKind::Quiet
+        }
+    }
+
+    pub const fn is_capture(&self) -> bool {
+        self.kind().is_capture()
+    }
+
+    pub const fn is_promotion(&self) -> bool {
+        self.kind().is_promotion()
+    }
+
+    pub const fn is_castle(&self) -> bool {
+        self.kind().is_castle()
+    }
+
+    pub const fn promotion(&self) -> Option<PromotionPiece> {
+        self.kind().promotion_piece()
+    }
+
+    pub const fn is_null(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the raw 16-bit encoding
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+}
+
+impl From<Move> for u16 {
+    fn from(mv: Move) -> Self {
+        mv.0
+    }
+}
+
+impl TryFrom<u16> for Move {
+    type Error = MoveDecodeError;
+    fn try_from(bits: u16) -> Result<Self, Self::Error> {
+        match MoveKind::from_bits(bits >> 12) {
+            Some(_) => Ok(Move(bits)),
+            None => Err(MoveDecodeError(bits))
+        }
+    }
 }
 
+impl std::fmt::Debug for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self} ({:?})", self.kind())
+    }
+}
+
+/// Formats the move in UCI long algebraic notation, e.g. `e7e8q`
 impl std::fmt::Display for Move {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}{}{}", self.from, self.to, 
-            if let Some(flags) = &self.flags {
-                let MoveFlags::Promotion(promo) = flags;
-                format!("{promo}")
-            } else {String::new()}
-        )
+        write!(f, "{}{}", self.from(), self.to())?;
+        if let Some(promo) = self.promotion() {
+            write!(f, "{}", promo.to_string().to_lowercase())?;
+        }
+        Ok(())
+    }
+}
+
+/// Fixed-capacity move buffer living on the stack
+///
+/// No legal chess position has more than 218 moves, so generation can write
+/// straight into this list without ever touching the heap.
+#[derive(Clone)]
+pub struct MoveList {
+    moves: [Move; MoveList::CAPACITY],
+    len: usize
+}
+
+impl MoveList {
+    pub const CAPACITY: usize = 256;
+
+    pub const fn new() -> Self {
+        MoveList {
+            moves: [Move::NULL; MoveList::CAPACITY],
+            len: 0
+        }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, mv: Move) {
+        debug_assert!(self.len < MoveList::CAPACITY, "MoveList overflow");
+        self.moves[self.len] = mv;
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<Move> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(self.moves[self.len])
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    pub fn as_slice(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [Move] {
+        &mut self.moves[..self.len]
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Move> {
+        self.as_slice().iter()
+    }
+
+    /// Sorts the moves by descending score, best move first
+    pub fn sort_by_score<F: FnMut(&Move) -> i32>(&mut self, mut score: F) {
+        self.as_mut_slice().sort_unstable_by_key(|mv| std::cmp::Reverse(score(mv)));
+    }
+
+    /// Keeps only the moves for which `f` returns `true`, preserving order
+    pub fn retain<F: FnMut(&Move) -> bool>(&mut self, mut f: F) {
+        let mut len = 0;
+        for i in 0..self.len {
+            if f(&self.moves[i]) {
+                self.moves[len] = self.moves[i];
+                len += 1;
+            }
+        }
+        self.len = len;
+    }
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        MoveList::new()
+    }
+}
+
+impl std::ops::Deref for MoveList {
+    type Target = [Move];
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl std::ops::DerefMut for MoveList {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_slice()
+    }
+}
+
+impl<'a> std::iter::IntoIterator for &'a MoveList {
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct MoveListIterator {
+    list: MoveList,
+    idx: usize
+}
+
+impl std::iter::IntoIterator for MoveList {
+    type Item = Move;
+    type IntoIter = MoveListIterator;
+    fn into_iter(self) -> Self::IntoIter {
+        MoveListIterator { list: self, idx: 0 }
+    }
+}
+
+impl Iterator for MoveListIterator {
+    type Item = Move;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.idx < self.list.len {
+            let mv = self.list.moves[self.idx];
+            self.idx += 1;
+            return Some(mv);
+        }
+        None
+    }
+}
+
+impl std::fmt::Debug for MoveList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter().map(|mv| format!("{mv}"))).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{moves::{Move, MoveKind, MoveList}, piece::PromotionPiece, square::Square};
+
+    fn quiet(from: usize, to: usize) -> Move {
+        Move::new(Square(from), Square(to), MoveKind::Quiet)
+    }
+
+    #[test]
+    fn test_move_encoding() {
+        let mv = Move::new(Square(52), Square(61), MoveKind::KnightPromotionCapture);
+        assert_eq!(mv.from(), Square(52));
+        assert_eq!(mv.to(), Square(61));
+        assert!(mv.is_capture() && mv.is_promotion());
+        assert_eq!(mv.promotion(), Some(PromotionPiece::Knight));
+        assert_eq!(Move::try_from(u16::from(mv)).unwrap(), mv);
+        assert!(Move::try_from(0x6000).is_err());
+        assert_eq!(std::mem::size_of::<Move>(), 2);
+    }
+    #[test]
+    fn test_move_list_push_pop() {
+        let mut list = MoveList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.pop(), None);
+        list.push(quiet(12, 28));
+        list.push(quiet(6, 21));
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0], quiet(12, 28));
+        assert_eq!(list.pop(), Some(quiet(6, 21)));
+        assert_eq!(list.pop(), Some(quiet(12, 28)));
+        assert_eq!(list.pop(), None);
+        list.push(quiet(1, 18));
+        list.clear();
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_move_list_retain() {
+        let mut list = MoveList::new();
+        for to in 16..24 {
+            list.push(quiet(to - 8, to));
+        }
+        list.retain(|mv| mv.to().0 % 2 == 0);
+        assert_eq!(list.as_slice(), &[quiet(8, 16), quiet(10, 18), quiet(12, 20), quiet(14, 22)]);
+        list.retain(|_| false);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_move_list_sort_by_score() {
+        let mut list = MoveList::new();
+        for to in [20, 40, 30, 10] {
+            list.push(quiet(0, to));
+        }
+        list.sort_by_score(|mv| mv.to().0 as i32);
+        let order: Vec<usize> = list.iter().map(|mv| mv.to().0).collect();
+        assert_eq!(order, [40, 30, 20, 10]);
+    }
+
+    #[test]
+    fn test_move_list_iterators() {
+        let mut list = MoveList::new();
+        for to in 16..20 {
+            list.push(quiet(to - 8, to));
+        }
+        let borrowed: Vec<Move> = (&list).into_iter().copied().collect();
+        let owned: Vec<Move> = list.clone().into_iter().collect();
+        assert_eq!(borrowed, owned);
+        assert_eq!(owned, [quiet(8, 16), quiet(9, 17), quiet(10, 18), quiet(11, 19)]);
+        // Slots past the length are never yielded
+        list.pop();
+        assert_eq!(list.into_iter().count(), 3);
+    }
+
+    #[test]
+    fn test_move_list_capacity() {
+        let mut list = MoveList::new();
+        for i in 0..MoveList::CAPACITY {
+            list.push(quiet(i % 64, (i + 1) % 64));
+        }
+        assert_eq!(list.len(), MoveList::CAPACITY);
+        assert_eq!(list.into_iter().count(), MoveList::CAPACITY);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_move_list_overflow() {
+        let mut list = MoveList::new();
+        for _ in 0..=MoveList::CAPACITY {
+            list.push(quiet(12, 28));
+        }
     }
 }
@@ -1,4 +1,4 @@
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Piece {
     King,
     Queen,
@@ -8,7 +8,7 @@ pub enum Piece {
     Pawn
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PromotionPiece {
     Queen,
     Rook,
@@ -16,14 +16,47 @@ pub enum PromotionPiece {
     Knight
 }
 
+impl From<PromotionPiece> for Piece {
+    fn from(promotion: PromotionPiece) -> Self {
+        match promotion {
+            PromotionPiece::Queen => Piece::Queen,
+            PromotionPiece::Rook => Piece::Rook,
+            PromotionPiece::Bishop => Piece::Bishop,
+            PromotionPiece::Knight => Piece::Knight
+        }
+    }
+}
+
+/// Symbols used to write each piece in move notation, indexed like `Piece`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PieceLetters(pub [&'static str; 6]);
+
+impl PieceLetters {
+    pub const ENGLISH: PieceLetters = PieceLetters(["K", "Q", "R", "B", "N", "P"]);
+    pub const GERMAN: PieceLetters = PieceLetters(["K", "D", "T", "L", "S", "B"]);
+    pub const FRENCH: PieceLetters = PieceLetters(["R", "D", "T", "F", "C", "P"]);
+    /// Pawns are written "p" so they can't be mistaken for the knight
+    pub const DUTCH: PieceLetters = PieceLetters(["K", "D", "T", "L", "P", "p"]);
+    pub const SPANISH: PieceLetters = PieceLetters(["R", "D", "T", "A", "C", "P"]);
+    pub const ITALIAN: PieceLetters = PieceLetters(["R", "D", "T", "A", "C", "P"]);
+    pub const PORTUGUESE: PieceLetters = PieceLetters(["R", "D", "T", "B", "C", "P"]);
+    pub const SWEDISH: PieceLetters = PieceLetters(["K", "D", "T", "L", "S", "B"]);
+    /// Unicode chess symbols, printed for both colours as in most chess literature
+    pub const FIGURINE: PieceLetters = PieceLetters(["\u{2654}", "\u{2655}", "\u{2656}", "\u{2657}", "\u{2658}", "\u{2659}"]);
+
+    pub const fn get(&self, piece: Piece) -> &'static str {
+        self.0[piece as usize]
+    }
+}
+
+impl std::fmt::Display for Piece {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", PieceLetters::ENGLISH.get(*self))
+    }
+}
+
 impl std::fmt::Display for PromotionPiece {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let char = match self {
-            Self::Queen => 'Q',
-            Self::Rook => 'R',
-            Self::Bishop => 'B',
-            Self::Knight => 'N'
-        };
-        write!(f, "{char}")
+        write!(f, "{}", Piece::from(*self))
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,12 @@
+//! Generated by `cargo run --release -p types --bin find_magics -- black 7 20`
+
+use crate::square::Square;
+
+/// Fixed-shift magics, every bishop index takes 9 bits and every rook index 12
+pub const BISHOP_BLACK_MAGICS: [u64; Square::NUM] = [0x8d8400601ef82001, 0xc37fc10fefdbfffa, 0xeffefff01bfffe2c, 0x2340802004004810, 0xda1fc3fe7e7af89a, 0xc0101eff00811580, 0x3ee8108001008290, 0xd64ff04020040081, 0x681b824020208003, 0xd1feffc06017fc20, 0xee430100082000ec, 0x28de008020041514, 0xe93f1fc3fe7527d2, 0x3465bfe0feff7e1e, 0x94f610305fc00080, 0x748013f81ffeffc0, 0x317e7fff821fcffd, 0x6e01403fa01f7803, 0x5bfefffdfff0fbff, 0xa840210200800f92, 0xd0e0205f00080402, 0x617fefb07dff7fd1, 0x7d07f7eff03f3ffd, 0x5a9403abdff03ffd, 0xb3808220007fe100, 0x3dbf01057fdfbff0, 0x7010804102020009, 0x16effbfb3fbffff, 0x23ebfbff7c3effce, 0x81c0404020040207, 0xe6800fdbfff07ffe, 0x767f9ffd63f81ffa, 0x7fa002006b018061, 0xcdbffeffd23f3fd0, 0x630020100008100, 0x46eaff7f7fe7fdfd, 0xaca004010101a008, 0x93ffc00fbfcf7fc0, 0xcdffef7ff7a7dfdf, 0x5b80c003f19007f8, 0xccd060007e1a8081, 0xd0c7ffdfc20b7fc0, 0x943400fefeff7f80, 0x7b43140802000040, 0x450f020040100101, 0xb500801fc0400080, 0x76fdffffeff817ec, 0x99ffffb7dff613fc, 0x901000203e8079be, 0x5ba000101f403900, 0xa1f918000f80816e, 0xb90643dff7ffbff3, 0x20008100202044, 0xe09200802080203c, 0xe7ffc11fdfdffbfc, 0x73000080081005f1, 0x84d3fff09fbeff94, 0xb7f3f3ffefe0bfc2, 0x4d02c6b7fff07f7f, 0x187a88889c080040, 0xa41c96b9feffdfe0, 0x8efd5e8000405020, 0xe41c000120200813, 0x58fdff9ff107f81c];
+pub const ROOK_BLACK_MAGICS: [u64; Square::NUM] = [0x8f80021a40000490, 0xffdffff7ffefffdd, 0xffbff7ffefffbffc, 0xffbffbffbff7fffe, 0xffbffbfffdffbfff, 0xffdfff7fdffefdfe, 0x40010000400080, 0x7a100046842a000c, 0x79ffe7fe370bfffd, 0x1080100008040010, 0xb67fbff7ffc23ff0, 0x6d80200400200201, 0x7a80200200200100, 0x3b00200100008020, 0xbf7fe800437fffdf, 0x3600200020004081, 0x6ac0002000100020, 0x7984001000080014, 0x7c87ffeffffdfff0, 0xcf5dffdffbffdffe, 0xabf2002001002002, 0x6a98ffdfff7fdfff, 0x1a0004040008001, 0x2700802000400020, 0xffbfdfffeff7fff0, 0x9efff7ffeffbfff0, 0x7444020008010008, 0xe43ffdffdffbffdf, 0xa1fffeffdffdffe0, 0x37e0010020008020, 0xe5c400040008001, 0x87a03fffbfffff80, 0x98bfffefffdfffe0, 0x4304000800100013, 0xe03fbff77fbefff0, 0xdb5fdffdffdffbff, 0xf9cfdffeffdffe00, 0x748200100200080, 0x3890200080200040, 0xa6a77fe000200040, 0x7efff3fe31ffe800, 0x2110080201000401, 0x6e803dc008004010, 0xb7c2001ffbffdfff, 0x8431002002002001, 0x63e1002000802001, 0x6c80400080004001, 0x903bbc008a004402, 0x89fffcf9fb2cffb0, 0xb17fff7f817fbfa8, 0xa5ffffbfb7fc7fe8, 0xfe1ffdffdffbffe0, 0x9870020020010020, 0xd87fff7fdffeffe0, 0x7160200040008020, 0x607e27fff64fffd8, 0x8400004428310082, 0xcd7ffeeedcf67eff, 0x7ec5fffed0f3e7fa, 0x9927efdff7fbbffe, 0x967fffdbe85fe7de, 0x80f098000f641002, 0x5033fff7ef9b6ffc, 0xa730000669456926];
+
+/// Added to the indices with wrapping arithmetic, the tables of all squares overlap in one
+pub const BISHOP_BLACK_OFFSETS: [usize; Square::NUM] = [0x4cbd, 0x70c7, 0x4f09, 0xf150, 0x61f9, 0xf252, 0x61b1, 0x4ebf, 0x709d, 0x7125, 0x639e, 0x180f9, 0x6257, 0x5964, 0x5a5f, 0x186e5, 0x61e7, 0xf51b, 0x58f4, 0x48ac, 0x492d, 0x598d, 0x6353, 0x5043, 0x18292, 0x1821a, 0x4a3c, 0x18cb9, 0x18eb5, 0x4adf, 0x5562, 0x6310, 0x7003, 0x7184, 0x502f, 0x190b5, 0x192a8, 0x514c, 0x18424, 0x62af, 0x641b, 0x61b7, 0x520d, 0x5519, 0x552d, 0x5689, 0x6ff7, 0x4cf7, 0x5905, 0x5f9f, 0x5278, 0x5916, 0x1832c, 0x18577, 0x1384, 0x1300, 0x6269, 0x640b, 0x55d2, 0x6144, 0x1871d, 0x7182, 0x1325, 0x6184];
+pub const ROOK_BLACK_OFFSETS: [usize; Square::NUM] = [0xfffffffffffffffe, 0xa97f, 0xb17d, 0xb97d, 0xc17d, 0x8973, 0xc185, 0xff8, 0xeb72, 0x10b30, 0x10590, 0x11500, 0x119d0, 0x119d8, 0x17f30, 0x7cd5, 0xcc2d, 0x121c0, 0x3aa5, 0x1271e, 0x12ce7, 0x12ad2, 0x12a42, 0x93f0, 0x9179, 0x13418, 0x1096b, 0x13c6c, 0x13c48, 0x13da6, 0x13f0d, 0xd3fc, 0xdb0d, 0x14658, 0xff44, 0x14fde, 0x155c5, 0x14e9c, 0x155b1, 0x9eea, 0xe491, 0x10839, 0x1007e, 0x15eb5, 0x1626b, 0x16466, 0x168f0, 0x784c, 0xf18a, 0x1850e, 0x1807a, 0x1720a, 0x173af, 0x177b0, 0x1793e, 0xf5bd, 0x2fc6, 0x60e3, 0x2ff1, 0x3f6f, 0x4cd5, 0x58f0, 0x68d7, 0x1fe7];
+pub const BLACK_MAGIC_SIZE: usize = 0x194a7;
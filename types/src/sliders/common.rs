@@ -9,11 +9,11 @@ pub const ROOK_BLOCKERS: [u64; Square::NUM] = [0x101010101017e, 0x202020202027c,
 pub const BISHOP_ATTACKS: [u64; Square::NUM] = [0x8040201008040200, 0x80402010080500, 0x804020110a00, 0x8041221400, 0x182442800, 0x10204885000, 0x102040810a000, 0x102040810204000, 0x4020100804020002, 0x8040201008050005, 0x804020110a000a, 0x804122140014, 0x18244280028, 0x1020488500050, 0x102040810a000a0, 0x204081020400040, 0x2010080402000204, 0x4020100805000508, 0x804020110a000a11, 0x80412214001422, 0x1824428002844, 0x102048850005088, 0x2040810a000a010, 0x408102040004020, 0x1008040200020408, 0x2010080500050810, 0x4020110a000a1120, 0x8041221400142241, 0x182442800284482, 0x204885000508804, 0x40810a000a01008, 0x810204000402010, 0x804020002040810, 0x1008050005081020, 0x20110a000a112040, 0x4122140014224180, 0x8244280028448201, 0x488500050880402, 0x810a000a0100804, 0x1020400040201008, 0x402000204081020, 0x805000508102040, 0x110a000a11204080, 0x2214001422418000, 0x4428002844820100, 0x8850005088040201, 0x10a000a010080402, 0x2040004020100804, 0x200020408102040, 0x500050810204080, 0xa000a1120408000, 0x1400142241800000, 0x2800284482010000, 0x5000508804020100, 0xa000a01008040201, 0x4000402010080402, 0x2040810204080, 0x5081020408000, 0xa112040800000, 0x14224180000000, 0x28448201000000, 0x50880402010000, 0xa0100804020100, 0x40201008040201,];
 pub const ROOK_ATTACKS: [u64; Square::NUM] = [0x1010101010101fe, 0x2020202020202fd, 0x4040404040404fb, 0x8080808080808f7, 0x10101010101010ef, 0x20202020202020df, 0x40404040404040bf, 0x808080808080807f, 0x10101010101fe01, 0x20202020202fd02, 0x40404040404fb04, 0x80808080808f708, 0x101010101010ef10, 0x202020202020df20, 0x404040404040bf40, 0x8080808080807f80, 0x101010101fe0101, 0x202020202fd0202, 0x404040404fb0404, 0x808080808f70808, 0x1010101010ef1010, 0x2020202020df2020, 0x4040404040bf4040, 0x80808080807f8080, 0x1010101fe010101, 0x2020202fd020202, 0x4040404fb040404, 0x8080808f7080808, 0x10101010ef101010, 0x20202020df202020, 0x40404040bf404040, 0x808080807f808080, 0x10101fe01010101, 0x20202fd02020202, 0x40404fb04040404, 0x80808f708080808, 0x101010ef10101010, 0x202020df20202020, 0x404040bf40404040, 0x8080807f80808080, 0x101fe0101010101, 0x202fd0202020202, 0x404fb0404040404, 0x808f70808080808, 0x1010ef1010101010, 0x2020df2020202020, 0x4040bf4040404040, 0x80807f8080808080, 0x1fe010101010101, 0x2fd020202020202, 0x4fb040404040404, 0x8f7080808080808, 0x10ef101010101010, 0x20df202020202020, 0x40bf404040404040, 0x807f808080808080, 0xfe01010101010101, 0xfd02020202020202, 0xfb04040404040404, 0xf708080808080808, 0xef10101010101010, 0xdf20202020202020, 0xbf40404040404040, 0x7f80808080808080,];
 
-pub const BISHOP_MAGICS: [u64; Square::NUM] = [0x1408220c1020081, 0x8110648104002150, 0x1408080100f90000, 0x41080a0022009000, 0xa061000000004, 0x81010840004110, 0x10880402201008, 0x8000206304206010, 0x4008200886180040, 0x14808c1004201, 0x80604022000, 0x1000c1142000002, 0x1480920211800000, 0x8000008804410500, 0x2302208811101000, 0x120a10c404010800, 0x108004011c10200, 0x54000941040408, 0x4910000804801090, 0x334010801403002, 0xa003000090401006, 0x8000800818010808, 0x4010282251000, 0x88360304060200, 0x4804120140262802, 0x1004440090300094, 0x4028280010004140, 0x86528000400c070, 0x200101000010c000, 0x2011010043b00800, 0x9100c10c1a0802, 0xd00226100980c, 0xa0210c0411090, 0xc050c0009d000, 0x3211000110402, 0x421910800140040, 0xc40401804100, 0x80050c0100280906, 0x8412c00010080, 0x2200c110020080, 0x8088012821410840, 0x28188205048e8, 0x8052104030040800, 0x806018004300, 0x410280100400c04, 0x101010500a600240, 0x902102200802200, 0x8502082200821228, 0x1004040405050400, 0x100840301112000, 0x188202440c440041, 0x1814680442020020, 0x40031420200c0, 0x40200881084000, 0x20500c0304040180, 0x1004041184230202, 0x420940404020808, 0x1942010b880502, 0xc0148c00a800, 0x100101211a18800, 0x126002040486201, 0x4000204090010a48, 0x102001250200, 0x392a080848008020];
-pub const ROOK_MAGICS: [u64; Square::NUM] = [0xd80002080344000, 0x2840002000429001, 0x2100084011012002, 0x880042800801000, 0x8080040080480082, 0x9b00080100020c00, 0x6080060001000480, 0x92000904c0a40086, 0x200080028020c000, 0x1004000290180, 0x8803000200480, 0x2811001001210008, 0x442800800824400, 0x8000800200040080, 0x304000148860c10, 0x802002c02004485, 0x8020008080004000, 0x1000460025008201, 0x1004808030002000, 0x2210010010210089, 0x2808028001400, 0x8808006000400, 0x440048020130, 0x204020005c40081, 0x810c40008000208c, 0x252a00040005004, 0x200180100480, 0x108000880801000, 0x200240080080080, 0xa040020080240080, 0x8014820c00100108, 0x80008020c100, 0x2040004020801180, 0x225008c000402000, 0x424330041002002, 0x300300021002900, 0x80024e200a000600, 0x1400808200804400, 0x2000402000918, 0x88402000051, 0x400480008020, 0x2100020004a4000, 0xc820010022450030, 0x2301400852020020, 0x420c08000c008080, 0x804000402008080, 0x2010086002b0014, 0x100209108620004, 0x80100a200409200, 0x102000c0100440, 0x820048010082080, 0x30001208210100, 0xd000080004008180, 0x20c0060080040080, 0x128810110400, 0x48014c9044010200, 0x4042810196004022, 0x8008012050221c2, 0x200009004511, 0x101000910002065, 0x301000800100403, 0x100040012c801, 0x2008100600811804, 0x4a010400942142];
+pub const BISHOP_MAGICS: [u64; Square::NUM] = [0x40100100459180, 0x908014414004482, 0xc0910400a200a002, 0x8828215040000404, 0x1104102910000100, 0x311420040a0300, 0x8101044120080800, 0xc08a4040a015000, 0x200204250090108, 0x888208044501, 0x80001444039a0004, 0x228004440a810001, 0x484101104004408c, 0xc1931006904002, 0x806124104000, 0x22101041008820c4, 0x80048aa820848400, 0x24138901440408, 0x808020420202200, 0x1988080082044000, 0x2003000090400010, 0x1002001101010104, 0x21000441101082, 0x8210801210411812, 0x8083200070041010, 0x1200410840130, 0x24410c0410200, 0x40400400844100a0, 0x100404004010040, 0x4000d3000180a000, 0x1084000c94045a, 0x1812120045410880, 0x400105204110a012, 0x92a001102400, 0x240058006400a0, 0x2020081480082, 0x2600410040040040, 0xa108020810018801, 0x4024080880004400, 0x9108988210008600, 0xc80210040d1082, 0xc081108820002400, 0x100208020801000, 0x4060002204202801, 0x8202020e010400, 0x240302405210941, 0x920120210420221, 0x614a08281001201, 0x10200842008108c, 0x20240208040100, 0xa08056080c0086, 0x8020020042088080, 0x28b2000490441920, 0x4100622004012002, 0x4050408220200, 0x4010040140420810, 0x2844108200200, 0x820401024a222048, 0x104b44200940422, 0x1108090800420202, 0x4122000010020884, 0x30010242040c0c20, 0x18c044c4008200, 0xc80101480a0280];
+pub const ROOK_MAGICS: [u64; Square::NUM] = [0x80102040008000, 0x44c0004820009001, 0xc80081000200280, 0x2080048800100180, 0x1200020020040810, 0x900088400010002, 0x1080008001000200, 0x100002200508100, 0x1010800040008030, 0x44804000802004, 0x1002008110040, 0x485001001010c20, 0x2820808004000800, 0x2808004002200, 0x1000100040200, 0x414800080004100, 0x88000c000200041, 0x4420808020004008, 0x2010012004002800, 0x220042000810, 0x1828008000400, 0xa000880120100440, 0x105434000810010a, 0x2060000428324, 0x80822480044000, 0x240080020100020, 0x4080100080802000, 0x100080080084, 0x80080800400, 0x6000600181014, 0x4010400021008, 0x1000011a0002c284, 0x240284000800881, 0x10002001400050, 0x100080802000, 0x10004400400800, 0x206000422001008, 0x4010800400800200, 0x2004426001829, 0x408106000a44, 0x1000400220818000, 0xa410004020004000, 0x1529001220010040, 0x708000810008080, 0x10080100050010, 0x1e40020004008080, 0x419210140048, 0x881886485120004, 0x10482080010500, 0x4080400020100040, 0x4200041081100, 0xac00801000080080, 0x201008020401002, 0x60040002008080, 0x2905100802010400, 0x48145504008200, 0x219024200802212, 0xa20e58242003102, 0x80010010a0008c41, 0x801000420081001, 0xc001001042080045, 0x9021000400020801, 0x409000082000441, 0x4820004400248502];
 
 pub const BISHOP_OFFSETS: [usize; Square::NUM] = [0x0, 0x40, 0x60, 0x80, 0xa0, 0xc0, 0xe0, 0x100, 0x140, 0x160, 0x180, 0x1a0, 0x1c0, 0x1e0, 0x200, 0x220, 0x240, 0x260, 0x280, 0x300, 0x380, 0x400, 0x480, 0x4a0, 0x4c0, 0x4e0, 0x500, 0x580, 0x780, 0x980, 0xa00, 0xa20, 0xa40, 0xa60, 0xa80, 0xb00, 0xd00, 0xf00, 0xf80, 0xfa0, 0xfc0, 0xfe0, 0x1000, 0x1080, 0x1100, 0x1180, 0x1200, 0x1220, 0x1240, 0x1260, 0x1280, 0x12a0, 0x12c0, 0x12e0, 0x1300, 0x1320, 0x1340, 0x1380, 0x13a0, 0x13c0, 0x13e0, 0x1400, 0x1420, 0x1440];
-pub const BISHOP_SIZE: usize = 0x5184;
+pub const BISHOP_SIZE: usize = 0x1480;
 
 pub const ROOK_OFFSETS: [usize; Square::NUM] = [0x0, 0x1000, 0x1800, 0x2000, 0x2800, 0x3000, 0x3800, 0x4000, 0x5000, 0x5800, 0x5c00, 0x6000, 0x6400, 0x6800, 0x6c00, 0x7000, 0x7800, 0x8000, 0x8400, 0x8800, 0x8c00, 0x9000, 0x9400, 0x9800, 0xa000, 0xa800, 0xac00, 0xb000, 0xb400, 0xb800, 0xbc00, 0xc000, 0xc800, 0xd000, 0xd400, 0xd800, 0xdc00, 0xe000, 0xe400, 0xe800, 0xf000, 0xf800, 0xfc00, 0x10000, 0x10400, 0x10800, 0x10c00, 0x11000, 0x11800, 0x12000, 0x12400, 0x12800, 0x12c00, 0x13000, 0x13400, 0x13800, 0x14000, 0x15000, 0x15800, 0x16000, 0x16800, 0x17000, 0x17800, 0x18000];
-pub const ROOK_SIZE: usize = 0x19000;
\ No newline at end of file
+pub const ROOK_SIZE: usize = 0x19000;
@@ -16,3 +16,23 @@ pub fn subsets(mask: BitBoard) -> Vec<BitBoard> {
 pub fn magic_index(blockers: BitBoard, hash: u64, shift_count: usize) -> usize {
     (blockers.0.wrapping_mul(hash) >> shift_count) as usize
 }
+
+/// Index of a fixed-shift black magic, every bit outside the relevant blockers is
+/// set first so the indices of one square stay close together
+pub const fn black_magic_index(occupied: BitBoard, not_mask: u64, magic: u64, shift_count: usize) -> usize {
+    ((occupied.0 | not_mask).wrapping_mul(magic) >> shift_count) as usize
+}
+
+/// Packs the bits of `value` selected by `mask` into the low bits, in order, as BMI2 `pext` does
+pub const fn pext_software(value: u64, mut mask: u64) -> u64 {
+    let mut packed = 0;
+    let mut bit = 1;
+    while mask != 0 {
+        if value & mask & mask.wrapping_neg() != 0 {
+            packed |= bit;
+        }
+        mask &= mask - 1;
+        bit <<= 1;
+    }
+    packed
+}
@@ -1,3 +1,5 @@
+pub mod black_magic;
 pub mod common;
 pub mod dumb7fill;
 pub mod magic;
+pub mod search;
@@ -0,0 +1,304 @@
+//! Magic number search, used by the `find_magics` tool to regenerate the constants
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    bitboard::BitBoard,
+    slider::Slider,
+    sliders::magic::{black_magic_index, magic_index, subsets},
+    square::Square,
+};
+
+/// Small seedable SplitMix64 generator, so searches are reproducible
+pub struct Rng(u64);
+
+impl Rng {
+    pub const fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Magics with few bits set are far more likely to work
+    pub fn sparse(&mut self) -> u64 {
+        self.next_u64() & self.next_u64() & self.next_u64()
+    }
+}
+
+/// Every blocker subset of a slider on `sq` with the attacks it leaves
+pub fn occupancies(slider: Slider, sq: Square) -> Vec<(BitBoard, BitBoard)> {
+    subsets(slider.blockers(sq))
+        .into_iter()
+        .map(|subset| (subset, slider.pseudo_moves(sq, subset)))
+        .collect()
+}
+
+/// Finds a fancy magic for `sq` using exactly as many index bits as there are
+/// relevant blockers, returning the magic and its shift
+pub fn fancy_magic(slider: Slider, sq: Square, rng: &mut Rng) -> (u64, usize) {
+    let mask = slider.blockers(sq);
+    let shift = 64 - mask.len() as usize;
+    let occupancies = occupancies(slider, sq);
+    let mut table = vec![None; 1 << mask.len()];
+    loop {
+        let magic = rng.sparse();
+        // The top byte of the product must be well mixed for the index to be
+        if (mask.0.wrapping_mul(magic) >> 56).count_ones() < 6 {
+            continue;
+        }
+        table.fill(None);
+        let valid = occupancies.iter().all(|&(subset, attacks)| {
+            let entry = &mut table[magic_index(subset, magic, shift)];
+            *entry.get_or_insert(attacks) == attacks
+        });
+        if valid {
+            return (magic, shift);
+        }
+    }
+}
+
+/// Indices a black magic uses for one square, with the attacks stored at each
+#[derive(Clone, Debug, PartialEq)]
+pub struct Segment {
+    pub entries: Vec<(usize, u64)>,
+}
+
+impl Segment {
+    pub fn min(&self) -> usize {
+        self.entries[0].0
+    }
+
+    /// Number of table entries between the lowest and highest index
+    pub fn span(&self) -> usize {
+        self.entries[self.entries.len() - 1].0 - self.min() + 1
+    }
+
+    /// Whether both segments store the same attacks at the same distances, so they
+    /// pack the same way
+    pub fn same_shape(&self, other: &Segment) -> bool {
+        self.entries.len() == other.entries.len()
+            && self.entries.iter().zip(&other.entries).all(|(&(a, x), &(b, y))| a - self.min() == b - other.min() && x == y)
+    }
+}
+
+/// Returns the indices `magic` maps the blockers of `sq` to, unless two occupancies
+/// with different attacks collide
+pub fn black_magic_segment(slider: Slider, sq: Square, magic: u64, occupancies: &[(BitBoard, BitBoard)]) -> Option<Segment> {
+    let not_mask = !slider.blockers(sq).0;
+    let shift = 64 - slider.shift_count();
+    let mut table = vec![None; 1 << slider.shift_count()];
+    for &(subset, attacks) in occupancies {
+        let entry = &mut table[black_magic_index(subset, not_mask, magic, shift)];
+        if *entry.get_or_insert(attacks.0) != attacks.0 {
+            return None;
+        }
+    }
+    let entries = table.into_iter().enumerate().filter_map(|(index, attacks)| Some((index, attacks?))).collect();
+    Some(Segment { entries })
+}
+
+/// Table the segments of all squares are packed into. A segment may overlap entries
+/// that are unused or already hold the same attacks.
+#[derive(Default)]
+pub struct PackedTable {
+    entries: Vec<Option<u64>>,
+    /// Unused entries, in order
+    holes: Vec<usize>,
+}
+
+impl PackedTable {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the lowest position the first entry of `segment` can be stored at
+    pub fn fit(&self, segment: &Segment) -> usize {
+        let min = segment.min();
+        let fits = |start: usize| segment.entries.iter().all(|&(index, attacks)| {
+            self.entries.get(start + index - min).copied().flatten().is_none_or(|stored| stored == attacks)
+        });
+        // The first entry has to go into a hole or past the end, where everything fits
+        self.holes.iter().copied().find(|&start| fits(start)).unwrap_or(self.len())
+    }
+
+    /// Returns how many entries placing `segment` at `start` adds to the table
+    pub fn growth(&self, segment: &Segment, start: usize) -> usize {
+        self.len().max(start + segment.span()) - self.len()
+    }
+
+    /// Stores `segment` from `start` on and returns the offset to add to its indices
+    /// with wrapping arithmetic
+    pub fn place(&mut self, segment: &Segment, start: usize) -> usize {
+        let min = segment.min();
+        let end = start + segment.span();
+        if self.entries.len() < end {
+            self.entries.resize(end, None);
+        }
+        for &(index, attacks) in &segment.entries {
+            self.entries[start + index - min] = Some(attacks);
+        }
+        self.holes = (0..self.entries.len()).filter(|&i| self.entries[i].is_none()).collect();
+        start.wrapping_sub(min)
+    }
+
+    /// Returns the entries, unused ones zeroed
+    pub fn into_table(self) -> Vec<u64> {
+        self.entries.into_iter().map(Option::unwrap_or_default).collect()
+    }
+}
+
+/// Searches black magics for `sq` with the fixed shift of the slider for `budget` and
+/// returns up to `keep` of them with the smallest spans, smallest first. Packing then
+/// picks the one that fits the table best.
+///
+/// Candidates are sparse numbers or their negation, with which the index is computed
+/// from the empty instead of the occupied squares. Most candidates flip a few bits of
+/// the current magic, since good magics tend to be close to each other, and the search
+/// starts over from a random one when that stops finding better magics for a while.
+pub fn black_magics(slider: Slider, sq: Square, rng: &mut Rng, budget: Duration, keep: usize) -> Vec<(u64, Segment)> {
+    const RESTART: u32 = 2000;
+    let occupancies = occupancies(slider, sq);
+    let start = Instant::now();
+    let mut best: Vec<(u64, Segment)> = vec![];
+    // Magic the current search flips bits of, with its span
+    let mut current: Option<(u64, usize)> = None;
+    let mut stale = 0;
+    while best.is_empty() || start.elapsed() < budget {
+        let magic = match current {
+            Some((magic, _)) if !rng.next_u64().is_multiple_of(4) => {
+                let flips = 1 + rng.next_u64() % 3;
+                (0..flips).fold(magic, |magic, _| magic ^ 1 << (rng.next_u64() % 64))
+            }
+            _ if rng.next_u64().is_multiple_of(2) => rng.sparse().wrapping_neg(),
+            _ => rng.sparse()
+        };
+        stale += 1;
+        if stale > RESTART {
+            (current, stale) = (None, 0);
+        }
+        let Some(segment) = black_magic_segment(slider, sq, magic, &occupancies) else {
+            continue;
+        };
+        let span = segment.span();
+        if current.is_none_or(|(_, current)| span <= current) {
+            if current.is_none_or(|(_, current)| span < current) {
+                stale = 0;
+            }
+            current = Some((magic, span));
+        }
+        let worst = best.last().map_or(usize::MAX, |(_, worst)| worst.span());
+        if (best.len() < keep || span < worst) && best.iter().all(|(_, found)| !found.same_shape(&segment)) {
+            let at = best.partition_point(|(_, found)| found.span() <= span);
+            best.insert(at, (magic, segment));
+            best.truncate(keep);
+        }
+    }
+    best
+}
+
+/// Flips bits of the `found` magics for `sq` for `budget`, looking for one whose segment
+/// grows `table` less than any of them, typically by fitting into the unused entries of
+/// a sparse segment. Returns the magic, its segment and the position to place it at.
+pub fn fit_black_magic(slider: Slider, sq: Square, rng: &mut Rng, budget: Duration, table: &PackedTable, found: &[(u64, Segment)]) -> (u64, Segment, usize) {
+    const RESTART: u32 = 500;
+    let occupancies = occupancies(slider, sq);
+    let start = Instant::now();
+    // Segments that grow the table equally are told apart by their span
+    let fit = |segment: &Segment| {
+        let position = table.fit(segment);
+        ((table.growth(segment, position), segment.span()), position)
+    };
+    let (mut best, mut best_growth) = found.iter()
+        .map(|(magic, segment)| {
+            let (growth, position) = fit(segment);
+            ((*magic, segment.clone(), position), growth)
+        })
+        .min_by_key(|&(_, growth)| growth)
+        .expect("at least one magic is found");
+    let (mut current, mut current_growth) = (best.0, best_growth);
+    let mut stale = 0;
+    while start.elapsed() < budget {
+        stale += 1;
+        if stale > RESTART {
+            let (magic, segment) = &found[(rng.next_u64() % found.len() as u64) as usize];
+            (current, current_growth, stale) = (*magic, fit(segment).0, 0);
+        }
+        let flips = 1 + rng.next_u64() % 3;
+        let magic = (0..flips).fold(current, |magic, _| magic ^ 1 << (rng.next_u64() % 64));
+        let Some(segment) = black_magic_segment(slider, sq, magic, &occupancies) else {
+            continue;
+        };
+        let (growth, position) = fit(&segment);
+        if growth <= current_growth {
+            if growth < current_growth {
+                stale = 0;
+            }
+            (current, current_growth) = (magic, growth);
+        }
+        if growth < best_growth {
+            (best, best_growth) = ((magic, segment, position), growth);
+        }
+    }
+    best
+}
+
+/// Places the segments in one table in the given order, letting them overlap wherever
+/// they agree or leave entries unused. Returns the table and, per segment, the offset
+/// to add to its indices with wrapping arithmetic.
+pub fn pack(segments: &[Segment]) -> (Vec<u64>, Vec<usize>) {
+    let mut table = PackedTable::default();
+    let offsets = segments.iter().map(|segment| table.place(segment, table.fit(segment))).collect();
+    (table.into_table(), offsets)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        slider::Slider,
+        sliders::{
+            magic::{black_magic_index, magic_index},
+            search::{black_magics, fancy_magic, occupancies, pack, Rng},
+        },
+        square::Square,
+    };
+
+    #[test]
+    fn test_search() {
+        let mut rng = Rng::new(0);
+        let (magic, shift) = fancy_magic(Slider::Bishop, Square(27), &mut rng);
+        assert_eq!(shift, 55);
+        let mut table = vec![0; 1 << (64 - shift)];
+        for (subset, attacks) in occupancies(Slider::Bishop, Square(27)) {
+            table[magic_index(subset, magic, shift)] = attacks.0;
+        }
+        for (subset, attacks) in occupancies(Slider::Bishop, Square(27)) {
+            assert_eq!(table[magic_index(subset, magic, shift)], attacks.0);
+        }
+
+        // Packed black magics for a few bishop squares must still index correctly
+        let squares = [Square(0), Square(1), Square(56), Square(63)];
+        let found: Vec<_> = squares.iter().map(|&sq| black_magics(Slider::Bishop, sq, &mut rng, Duration::from_millis(10), 1).remove(0)).collect();
+        let segments: Vec<_> = found.iter().map(|(_, segment)| segment.clone()).collect();
+        let (table, offsets) = pack(&segments);
+        assert!(table.len() <= segments.iter().map(|segment| segment.span()).sum());
+        for (i, &sq) in squares.iter().enumerate() {
+            let not_mask = !Slider::Bishop.blockers(sq).0;
+            for (subset, attacks) in occupancies(Slider::Bishop, sq) {
+                let index = black_magic_index(subset, not_mask, found[i].0, 64 - Slider::Bishop.shift_count());
+                assert_eq!(table[index.wrapping_add(offsets[i])], attacks.0);
+            }
+        }
+    }
+}
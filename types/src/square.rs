@@ -2,7 +2,7 @@ use crate::bitboard::BitBoard;
 use std::error::Error;
 use thiserror::Error;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Square(pub usize);
 
 impl Square {
@@ -55,10 +55,10 @@ impl std::str::FromStr for Square {
             return Err(Box::new(SquareParseError::CharLenError(s.len())));
         }
         let mut chars = s.chars();
-        let file = chars.next().ok_or(SquareParseError::FileError)?.to_digit(10).ok_or(SquareParseError::FileError)?;
+        let file = chars.next().ok_or(SquareParseError::FileError)? as u32;
         let rank = chars.next().ok_or(SquareParseError::RankError)?.to_digit(10).ok_or(SquareParseError::RankError)?;
 
-        if file < 97 || file > 104 {
+        if !(97..=104).contains(&file) {
             return Err(Box::new(SquareParseError::FileError));
         }
         if rank == 0 || rank > 8 {
@@ -76,5 +76,7 @@ mod tests {
     #[test]
     fn test_square() {
         assert_eq!(format!("{}", Square(42)), String::from("c6"));
+        assert_eq!("c6".parse::<Square>().unwrap(), Square(42));
+        assert!("i1".parse::<Square>().is_err());
     }
 }